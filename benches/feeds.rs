@@ -0,0 +1,72 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use kisserss::error::FeedError;
+use kisserss::{atom, rss};
+
+#[path = "../greyxml/benches/corpus/mod.rs"]
+mod corpus;
+
+use corpus::{Shape, FIXTURES, SHAPES, SIZES};
+
+type Serialize<T> = fn(&[u8]) -> Result<T, FeedError>;
+
+fn bench_feed<T>(
+    c: &mut Criterion,
+    group: &str,
+    id: BenchmarkId,
+    document: &str,
+    items: usize,
+    serialize: Serialize<T>,
+) {
+    let mut group = c.benchmark_group(group);
+    group.throughput(Throughput::Bytes(document.len() as u64));
+    // Large documents take long enough per pass that the default 100 samples
+    // would run for minutes
+    if items >= 10_000 {
+        group.sample_size(10);
+    }
+    group.bench_with_input(id, document, |b, document| {
+        b.iter(|| serialize(black_box(document.as_bytes())).unwrap())
+    });
+    group.finish();
+}
+
+fn generated<T>(
+    c: &mut Criterion,
+    group: &str,
+    generate: fn(usize, Shape) -> String,
+    serialize: Serialize<T>,
+) {
+    for shape in SHAPES {
+        for items in SIZES {
+            let document = generate(items, shape);
+            let id = BenchmarkId::new(shape.name(), items);
+            bench_feed(c, group, id, &document, items, serialize);
+        }
+    }
+}
+
+fn fixtures(c: &mut Criterion) {
+    for (name, document) in FIXTURES {
+        let id = BenchmarkId::from_parameter(name);
+        bench_feed(c, "rss/fixtures", id, document, 0, |document| {
+            rss::Feed::serialize(document)
+        });
+    }
+}
+
+fn rss(c: &mut Criterion) {
+    generated(c, "rss", corpus::rss, |document| {
+        rss::Feed::serialize(document)
+    });
+}
+
+fn atom(c: &mut Criterion) {
+    generated(c, "atom", corpus::atom, |document| {
+        atom::Feed::serialize(document)
+    });
+}
+
+criterion_group!(benches, fixtures, rss, atom);
+criterion_main!(benches);
@@ -0,0 +1,11 @@
+#![no_main]
+
+// A malformed feed has to come back as an Err rather than taking the TUI down
+
+use kisserss::atom;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = atom::Feed::serialize(data);
+    let _ = atom::Feed::serialize_lenient(data);
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+// A malformed JSON Feed has to come back as an Err rather than taking the TUI down
+
+use kisserss::json;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = json::Feed::serialize(data);
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+// Any input must lex and tokenize to Ok or Err, never panic, in both XML and
+// HTML mode and through the streaming reader. Read leniently, a string always
+// comes out as tokens.
+
+use greyxml::{lex, tokenize, tokenize_html, Reader, StrReader};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        if let Ok(lexed) = lex(text) {
+            let _ = tokenize(&mut lexed.clone().into_iter());
+            let _ = tokenize_html(&mut lexed.into_iter());
+        }
+        for token in StrReader::lenient(text) {
+            assert!(token.is_ok(), "{token:?}");
+        }
+    }
+    Reader::new(data).for_each(drop);
+    Reader::html(data).for_each(drop);
+    Reader::lenient(data).for_each(drop);
+});
@@ -0,0 +1,182 @@
+// Synthetic feeds for the benches. Everything is built from the item index so
+// every run parses the same bytes, shared with the kisserss feed benches.
+
+use std::fmt::Write;
+
+// Item counts each shape is generated at
+pub const SIZES: [usize; 3] = [10, 1_000, 50_000];
+
+// How many elements deep the nested shape wraps its text
+const DEPTH: usize = 32;
+
+// The feeds in greyxml/tests, all RSS
+pub const FIXTURES: [(&str, &str); 3] = [
+    ("test.rss", include_str!("../../tests/test.rss")),
+    (
+        "@sarahgreywolf.rss",
+        include_str!("../../tests/@sarahgreywolf.rss"),
+    ),
+    ("Kisserss.rss", include_str!("../../tests/Kisserss.rss")),
+];
+
+#[derive(Clone, Copy, Debug)]
+pub enum Shape {
+    // Short text fields with the odd entity reference
+    Plain,
+    // Descriptions carrying their HTML in CDATA sections
+    CData,
+    // Enclosures, guids, categories and media elements with several attributes
+    Attributes,
+    // Descriptions wrapping their text in deeply nested markup
+    Nested,
+}
+
+pub const SHAPES: [Shape; 4] = [Shape::Plain, Shape::CData, Shape::Attributes, Shape::Nested];
+
+impl Shape {
+    pub fn name(self) -> &'static str {
+        match self {
+            Shape::Plain => "plain",
+            Shape::CData => "cdata",
+            Shape::Attributes => "attributes",
+            Shape::Nested => "nested",
+        }
+    }
+}
+
+fn nested(out: &mut String, i: usize) {
+    for depth in 0..DEPTH {
+        let _ = write!(out, "<div class=\"d{depth}\">");
+    }
+    let _ = write!(
+        out,
+        "<p>Nested text for item {i} with <b>bold</b> and <i>italic</i></p>"
+    );
+    for _ in 0..DEPTH {
+        out.push_str("</div>");
+    }
+}
+
+pub fn rss(items: usize, shape: Shape) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <rss version=\"2.0\" xmlns:media=\"http://search.yahoo.com/mrss/\" \
+         xmlns:content=\"http://purl.org/rss/1.0/modules/content/\">\n\
+         <channel>\n\
+         <title>Synthetic feed</title>\n\
+         <link>https://example.com/</link>\n\
+         <description>A generated feed for benchmarking</description>\n\
+         <language>en-gb</language>\n\
+         <ttl>60</ttl>\n",
+    );
+    for i in 0..items {
+        let _ = write!(
+            out,
+            "<item>\n<title>Item {i}: Tom &amp; Jerry</title>\n\
+             <link>https://example.com/items/{i}</link>\n\
+             <pubDate>Mon, 02 Jan 2023 15:04:05 +0000</pubDate>\n"
+        );
+        match shape {
+            Shape::Plain => {
+                let _ = write!(
+                    out,
+                    "<description>Plain text for item {i} with &lt;escaped&gt; markup</description>\n\
+                     <guid>https://example.com/items/{i}</guid>\n"
+                );
+            }
+            Shape::CData => {
+                let _ = write!(
+                    out,
+                    "<description><![CDATA[<p>Item {i} has <a href=\"https://example.com/{i}\">a \
+                     link</a> & <em>unescaped</em> markup</p>]]></description>\n\
+                     <content:encoded><![CDATA[<article><h1>Item {i}</h1><p>{}</p></article>]]>\
+                     </content:encoded>\n",
+                    "Lorem ipsum dolor sit amet. ".repeat(8)
+                );
+            }
+            Shape::Attributes => {
+                let _ = write!(
+                    out,
+                    "<guid isPermaLink=\"false\">urn:item:{i}</guid>\n\
+                     <enclosure url=\"https://example.com/{i}.mp3\" length=\"{}\" type=\"audio/mpeg\"/>\n\
+                     <category domain=\"https://example.com/tags\">tag{}</category>\n\
+                     <media:content url=\"https://example.com/{i}.jpg\" medium=\"image\" \
+                     type=\"image/jpeg\" width=\"640\" height=\"480\" fileSize=\"{}\" lang=\"en\"/>\n\
+                     <media:thumbnail url=\"https://example.com/{i}.thumb.jpg\" width=\"64\" \
+                     height=\"48\" time=\"12:05:01.123\"/>\n",
+                    i * 1024,
+                    i % 16,
+                    i * 512
+                );
+            }
+            Shape::Nested => {
+                out.push_str("<description>");
+                nested(&mut out, i);
+                out.push_str("</description>\n");
+            }
+        }
+        out.push_str("</item>\n");
+    }
+    out.push_str("</channel>\n</rss>\n");
+    out
+}
+
+pub fn atom(entries: usize, shape: Shape) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+         <title>Synthetic feed</title>\n\
+         <link href=\"https://example.com/\"/>\n\
+         <updated>2023-01-02T15:04:05Z</updated>\n\
+         <author><name>Bench</name></author>\n\
+         <id>urn:feed:synthetic</id>\n",
+    );
+    for i in 0..entries {
+        let _ = write!(
+            out,
+            "<entry>\n<title>Entry {i}: Tom &amp; Jerry</title>\n\
+             <id>urn:entry:{i}</id>\n\
+             <updated>2023-01-02T15:04:05Z</updated>\n"
+        );
+        match shape {
+            Shape::Plain => {
+                let _ = write!(
+                    out,
+                    "<link href=\"https://example.com/entries/{i}\"/>\n\
+                     <summary>Plain text for entry {i} with &lt;escaped&gt; markup</summary>\n"
+                );
+            }
+            Shape::CData => {
+                let _ = write!(
+                    out,
+                    "<link href=\"https://example.com/entries/{i}\"/>\n\
+                     <content type=\"html\"><![CDATA[<article><h1>Entry {i}</h1><p>{}</p>\
+                     </article>]]></content>\n",
+                    "Lorem ipsum dolor sit amet. ".repeat(8)
+                );
+            }
+            Shape::Attributes => {
+                let _ = write!(
+                    out,
+                    "<link rel=\"alternate\" type=\"text/html\" hreflang=\"en\" \
+                     href=\"https://example.com/entries/{i}\"/>\n\
+                     <link rel=\"enclosure\" type=\"audio/mpeg\" length=\"{}\" \
+                     href=\"https://example.com/{i}.mp3\"/>\n\
+                     <category term=\"tag{}\" scheme=\"https://example.com/tags\" label=\"Tag\"/>\n",
+                    i * 1024,
+                    i % 16
+                );
+            }
+            Shape::Nested => {
+                out.push_str(
+                    "<content type=\"xhtml\"><div xmlns=\"http://www.w3.org/1999/xhtml\">",
+                );
+                nested(&mut out, i);
+                out.push_str("</div></content>\n");
+            }
+        }
+        out.push_str("</entry>\n");
+    }
+    out.push_str("</feed>\n");
+    out
+}
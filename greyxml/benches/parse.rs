@@ -0,0 +1,72 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use greyxml::{lex, tokenize};
+
+mod corpus;
+
+use corpus::{Shape, FIXTURES, SHAPES, SIZES};
+
+// Large documents take long enough per pass that the default 100 samples
+// would run for minutes
+fn sample_size(items: usize) -> usize {
+    if items >= 10_000 {
+        10
+    } else {
+        100
+    }
+}
+
+fn bench_lex(c: &mut Criterion, group: &str, id: BenchmarkId, document: &str, items: usize) {
+    let mut group = c.benchmark_group(group);
+    group.throughput(Throughput::Bytes(document.len() as u64));
+    group.sample_size(sample_size(items));
+    group.bench_with_input(id, document, |b, document| {
+        b.iter(|| lex(black_box(document)).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_tokenize(c: &mut Criterion, group: &str, id: BenchmarkId, document: &str, items: usize) {
+    let mut group = c.benchmark_group(group);
+    group.throughput(Throughput::Bytes(document.len() as u64));
+    group.sample_size(sample_size(items));
+    group.bench_with_input(id, document, |b, document| {
+        b.iter_batched(
+            || lex(document).unwrap(),
+            |lexed| tokenize(&mut lexed.into_iter()).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn generated(c: &mut Criterion, format: &str, generate: fn(usize, Shape) -> String) {
+    for shape in SHAPES {
+        for items in SIZES {
+            let document = generate(items, shape);
+            let id = BenchmarkId::new(shape.name(), items);
+            bench_lex(c, &format!("lex/{format}"), id.clone(), &document, items);
+            bench_tokenize(c, &format!("tokenize/{format}"), id, &document, items);
+        }
+    }
+}
+
+fn fixtures(c: &mut Criterion) {
+    for (name, document) in FIXTURES {
+        let id = BenchmarkId::from_parameter(name);
+        bench_lex(c, "lex/fixtures", id.clone(), document, 0);
+        bench_tokenize(c, "tokenize/fixtures", id, document, 0);
+    }
+}
+
+fn rss(c: &mut Criterion) {
+    generated(c, "rss", corpus::rss);
+}
+
+fn atom(c: &mut Criterion) {
+    generated(c, "atom", corpus::atom);
+}
+
+criterion_group!(benches, fixtures, rss, atom);
+criterion_main!(benches);
@@ -0,0 +1,232 @@
+use std::io::BufRead;
+
+use crate::{Error, Name, Reader, Tokens};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Ast {
+    // The target and content of a processing instruction, such as the xml version info
+    DescriptorNode(String, String),
+    // Everything after `<!DOCTYPE`
+    DoctypeNode(String),
+    // Has a name and a series of attributes, its child nodes are kept by the Document
+    ElementNode(Name<'static>, Vec<Attribute>),
+    TextNode(String),
+    CDataNode(String),
+    CommentNode(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attribute {
+    pub name: Name<'static>,
+    pub value: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+#[derive(Clone, Debug, PartialEq)]
+struct NodeData {
+    ast: Ast,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+// An owned tree of every node in a document. Nodes live in a single Vec and
+// point at each other by index, so a Node can walk up to its parent as easily
+// as down to its children.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Document {
+    nodes: Vec<NodeData>,
+    // Descriptors, the doctype, the root element and anything around them
+    top_level: Vec<NodeId>,
+}
+
+pub fn parse_document<R: BufRead>(reader: R) -> Result<Document, Error> {
+    Document::from_tokens(Reader::new(reader))
+}
+
+impl Document {
+    // Everything is copied into the Document, so it can outlive whatever the
+    // tokens borrowed from
+    pub fn from_tokens<'a, I>(tokens: I) -> Result<Document, Error>
+    where
+        I: IntoIterator<Item = Result<Tokens<'a>, Error>>,
+    {
+        let mut document = Document::default();
+        let mut open: Vec<NodeId> = vec![];
+        // The element a ParameterName/ParameterValue belongs to
+        let mut has_parameters = None;
+        let mut parameter_name = None;
+        for token in tokens {
+            let parent = open.last().copied();
+            let ast = match token? {
+                Tokens::OpenNode(name) => {
+                    let id = document.push(Ast::ElementNode(name.into_owned(), vec![]), parent);
+                    open.push(id);
+                    has_parameters = Some(id);
+                    continue;
+                }
+                Tokens::ParameterName(name) => {
+                    parameter_name = Some(name.into_owned());
+                    continue;
+                }
+                Tokens::ParameterValue(value) => {
+                    let (Some(name), Some(id)) = (parameter_name.take(), has_parameters) else {
+                        continue;
+                    };
+                    if let Ast::ElementNode(_, attributes) = &mut document.nodes[id.0].ast {
+                        attributes.push(Attribute {
+                            name,
+                            value: value.into_owned(),
+                        });
+                    }
+                    continue;
+                }
+                Tokens::CloseNode(name) => {
+                    has_parameters = None;
+                    let depth = open.iter().rposition(|id| {
+                        document
+                            .get(*id)
+                            .name()
+                            .is_some_and(|open| open.qualified == name.qualified)
+                    });
+                    if let Some(depth) = depth {
+                        open.truncate(depth);
+                    }
+                    continue;
+                }
+                Tokens::Text(text) => Ast::TextNode(text.into_owned()),
+                Tokens::CData(text) => Ast::CDataNode(text.into_owned()),
+                Tokens::Comment(text) => Ast::CommentNode(text.into_owned()),
+                Tokens::ProcessingInstruction(target, content) => {
+                    Ast::DescriptorNode(target.into_owned(), content.into_owned())
+                }
+                Tokens::Doctype(doctype) => Ast::DoctypeNode(doctype.into_owned()),
+            };
+            has_parameters = None;
+            document.push(ast, parent);
+        }
+        Ok(document)
+    }
+
+    fn push(&mut self, ast: Ast, parent: Option<NodeId>) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(NodeData {
+            ast,
+            parent,
+            children: vec![],
+        });
+        match parent {
+            Some(parent) => self.nodes[parent.0].children.push(id),
+            None => self.top_level.push(id),
+        }
+        id
+    }
+
+    pub fn get(&self, id: NodeId) -> Node<'_> {
+        Node { document: self, id }
+    }
+
+    // The first element at the top level of the document
+    pub fn root(&self) -> Option<Node<'_>> {
+        self.children().find(|node| node.is_element())
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = Node<'_>> {
+        self.top_level.iter().map(|id| self.get(*id))
+    }
+}
+
+// A borrowed handle to one node of a Document
+#[derive(Clone, Copy, Debug)]
+pub struct Node<'a> {
+    document: &'a Document,
+    id: NodeId,
+}
+
+impl<'a> Node<'a> {
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    pub fn ast(&self) -> &'a Ast {
+        &self.document.nodes[self.id.0].ast
+    }
+
+    pub fn is_element(&self) -> bool {
+        matches!(self.ast(), Ast::ElementNode(..))
+    }
+
+    // The name of an element, or None for any other kind of node
+    pub fn name(&self) -> Option<&'a Name<'static>> {
+        match self.ast() {
+            Ast::ElementNode(name, _) => Some(name),
+            _ => None,
+        }
+    }
+
+    // Whether this is an element named `local_name` in `namespace`
+    pub fn is(&self, namespace: &str, local_name: &str) -> bool {
+        self.name()
+            .is_some_and(|name| name.is(namespace, local_name))
+    }
+
+    pub fn attributes(&self) -> &'a [Attribute] {
+        match self.ast() {
+            Ast::ElementNode(_, attributes) => attributes,
+            _ => &[],
+        }
+    }
+
+    // The value of the attribute written as `name`, prefix and all
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes()
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    pub fn parent(&self) -> Option<Node<'a>> {
+        let parent = self.document.nodes[self.id.0].parent?;
+        Some(self.document.get(parent))
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = Node<'a>> + 'a {
+        let document = self.document;
+        document.nodes[self.id.0]
+            .children
+            .iter()
+            .map(move |id| document.get(*id))
+    }
+
+    // Child elements, skipping over any text between them
+    pub fn elements(&self) -> impl Iterator<Item = Node<'a>> + 'a {
+        self.children().filter(|node| node.is_element())
+    }
+
+    // The first child element written as `name`, prefix and all
+    pub fn child(&self, name: &str) -> Option<Node<'a>> {
+        self.elements()
+            .find(|node| node.name().is_some_and(|node_name| node_name == name))
+    }
+
+    // The first child element named `local_name` in `namespace`
+    pub fn child_ns(&self, namespace: &str, local_name: &str) -> Option<Node<'a>> {
+        self.elements().find(|node| node.is(namespace, local_name))
+    }
+
+    // All of the text and CDATA inside this node and its descendants, in document order
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        text
+    }
+
+    fn collect_text(&self, text: &mut String) {
+        match self.ast() {
+            Ast::TextNode(content) | Ast::CDataNode(content) => text.push_str(content),
+            Ast::ElementNode(..) => self.children().for_each(|child| child.collect_text(text)),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::{self, BufRead, Chain, Cursor, Read};
+
+use encoding_rs::{Decoder, Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+// How much of the start of a document is looked through for its `<?xml?>` declaration
+const SNIFF_LENGTH: usize = 1024;
+
+// Turns a document in any encoding into UTF-8 as it is read. The encoding is
+// taken from the byte order mark, then the `encoding` of the `<?xml?>`
+// declaration, then the charset it was served with, falling back to UTF-8.
+// Anything that can't be decoded is replaced with U+FFFD.
+pub struct DecodingReader<R: BufRead> {
+    reader: Chain<Cursor<Vec<u8>>, R>,
+    decoder: Decoder,
+    encoding: &'static Encoding,
+    decoded: String,
+    position: usize,
+    finished: bool,
+}
+
+impl<R: BufRead> DecodingReader<R> {
+    // `charset` is the charset parameter of an HTTP Content-Type, if there was one
+    pub fn new(mut reader: R, charset: Option<&str>) -> io::Result<Self> {
+        let mut start = Vec::with_capacity(SNIFF_LENGTH);
+        while start.len() < SNIFF_LENGTH {
+            let buffer = reader.fill_buf()?;
+            if buffer.is_empty() {
+                break;
+            }
+            let length = buffer.len().min(SNIFF_LENGTH - start.len());
+            start.extend_from_slice(&buffer[..length]);
+            reader.consume(length);
+        }
+        let encoding = detect_encoding(&start, charset);
+        Ok(DecodingReader {
+            reader: Cursor::new(start).chain(reader),
+            decoder: encoding.new_decoder_with_bom_removal(),
+            encoding,
+            decoded: String::new(),
+            position: 0,
+            finished: false,
+        })
+    }
+
+    // The name of the encoding the document is being read as
+    pub fn encoding(&self) -> &'static str {
+        self.encoding.name()
+    }
+}
+
+impl<R: BufRead> Read for DecodingReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let length = available.len().min(out.len());
+        out[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+        Ok(length)
+    }
+}
+
+impl<R: BufRead> BufRead for DecodingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.position == self.decoded.len() && !self.finished {
+            self.decoded.clear();
+            self.position = 0;
+            let input = self.reader.fill_buf()?;
+            let last = input.is_empty();
+            let needed = self
+                .decoder
+                .max_utf8_buffer_length(input.len())
+                .unwrap_or(input.len() * 3 + 16);
+            self.decoded.reserve(needed);
+            let (_, read, _) = self
+                .decoder
+                .decode_to_string(input, &mut self.decoded, last);
+            self.reader.consume(read);
+            self.finished = last;
+        }
+        Ok(&self.decoded.as_bytes()[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.decoded.len());
+    }
+}
+
+// Works out the encoding from the first bytes of a document
+fn detect_encoding(start: &[u8], charset: Option<&str>) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(start) {
+        return encoding;
+    }
+    // Without a byte order mark UTF-16 can still be spotted by how the first `<` is laid out
+    match start {
+        [b'<', 0, _, 0, ..] => return UTF_16LE,
+        [0, b'<', 0, _, ..] => return UTF_16BE,
+        _ => {}
+    }
+    if let Some(encoding) = declared_encoding(start) {
+        // A document that reads as ASCII can't really be in UTF-16, whatever it says
+        if encoding != UTF_16LE && encoding != UTF_16BE {
+            return encoding;
+        }
+    }
+    charset
+        .and_then(|charset| Encoding::for_label(charset.trim().as_bytes()))
+        .filter(|encoding| *encoding != UTF_16LE && *encoding != UTF_16BE)
+        .unwrap_or(UTF_8)
+}
+
+// The `encoding` pseudo-attribute of the `<?xml ... ?>` declaration
+fn declared_encoding(start: &[u8]) -> Option<&'static Encoding> {
+    let declaration = start.strip_prefix(b"<?xml")?;
+    let end = declaration.windows(2).position(|w| w == b"?>")?;
+    let declaration = &declaration[..end];
+    let at = declaration.windows(8).position(|w| w == b"encoding")?;
+    let rest = declaration[at + 8..]
+        .trim_ascii_start()
+        .strip_prefix(b"=")?;
+    let rest = rest.trim_ascii_start();
+    let (&quote, rest) = rest.split_first()?;
+    if quote != b'"' && quote != b'\'' {
+        return None;
+    }
+    let label = &rest[..rest.iter().position(|&b| b == quote)?];
+    Encoding::for_label(label)
+}
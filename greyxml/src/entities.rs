@@ -0,0 +1,236 @@
+use std::borrow::Cow;
+
+// Which named entities are recognised when decoding text.
+// Numeric character references are always decoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Entities {
+    // Only the five entities predefined by XML
+    #[default]
+    Xml,
+    // The XML entities plus the HTML 4 named entity table
+    Html,
+}
+
+// The longest name in either table is 8 characters, anything longer than this
+// between a `&` and a `;` is treated as literal text
+const MAX_ENTITY_LENGTH: usize = 32;
+
+// Text without any references is handed back as it is, without copying it
+pub fn decode_entities(text: &str, entities: Entities) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let resolved = rest
+            .char_indices()
+            .take(MAX_ENTITY_LENGTH)
+            .find(|(_, c)| *c == ';')
+            .and_then(|(end, _)| resolve(&rest[1..end], entities).map(|c| (end, c)));
+        if let Some((end, c)) = resolved {
+            output.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            // Not a reference we understand, keep the `&` as written
+            output.push('&');
+            rest = &rest[1..];
+        }
+    }
+    output.push_str(rest);
+    Cow::Owned(output)
+}
+
+// Decodes text that may already be owned, reusing it when nothing changes
+pub(crate) fn decode(text: Cow<'_, str>, entities: Entities) -> Cow<'_, str> {
+    match text {
+        Cow::Borrowed(text) => decode_entities(text, entities),
+        Cow::Owned(text) => {
+            let decoded = match decode_entities(&text, entities) {
+                Cow::Owned(decoded) => Some(decoded),
+                Cow::Borrowed(_) => None,
+            };
+            Cow::Owned(decoded.unwrap_or(text))
+        }
+    }
+}
+
+fn resolve(name: &str, entities: Entities) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = if let Some(hex) = number.strip_prefix(['x', 'X']) {
+            if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            u32::from_str_radix(hex, 16).ok()?
+        } else {
+            if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            number.parse().ok()?
+        };
+        return char::from_u32(code).filter(|c| *c != '\0');
+    }
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "apos" => Some('\''),
+        "quot" => Some('"'),
+        _ if entities == Entities::Html => html_entity(name),
+        _ => None,
+    }
+}
+
+// U+00A0 to U+00FF, in order
+const LATIN_1: [&str; 96] = [
+    "nbsp", "iexcl", "cent", "pound", "curren", "yen", "brvbar", "sect", "uml", "copy", "ordf",
+    "laquo", "not", "shy", "reg", "macr", "deg", "plusmn", "sup2", "sup3", "acute", "micro",
+    "para", "middot", "cedil", "sup1", "ordm", "raquo", "frac14", "frac12", "frac34", "iquest",
+    "Agrave", "Aacute", "Acirc", "Atilde", "Auml", "Aring", "AElig", "Ccedil", "Egrave", "Eacute",
+    "Ecirc", "Euml", "Igrave", "Iacute", "Icirc", "Iuml", "ETH", "Ntilde", "Ograve", "Oacute",
+    "Ocirc", "Otilde", "Ouml", "times", "Oslash", "Ugrave", "Uacute", "Ucirc", "Uuml", "Yacute",
+    "THORN", "szlig", "agrave", "aacute", "acirc", "atilde", "auml", "aring", "aelig", "ccedil",
+    "egrave", "eacute", "ecirc", "euml", "igrave", "iacute", "icirc", "iuml", "eth", "ntilde",
+    "ograve", "oacute", "ocirc", "otilde", "ouml", "divide", "oslash", "ugrave", "uacute", "ucirc",
+    "uuml", "yacute", "thorn", "yuml",
+];
+
+// U+0391 to U+03A9 and U+03B1 to U+03C9, in order, U+03A2 is unassigned
+const GREEK_UPPER: [&str; 25] = [
+    "Alpha", "Beta", "Gamma", "Delta", "Epsilon", "Zeta", "Eta", "Theta", "Iota", "Kappa",
+    "Lambda", "Mu", "Nu", "Xi", "Omicron", "Pi", "Rho", "", "Sigma", "Tau", "Upsilon", "Phi",
+    "Chi", "Psi", "Omega",
+];
+const GREEK_LOWER: [&str; 25] = [
+    "alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta", "theta", "iota", "kappa",
+    "lambda", "mu", "nu", "xi", "omicron", "pi", "rho", "sigmaf", "sigma", "tau", "upsilon", "phi",
+    "chi", "psi", "omega",
+];
+
+// The rest of https://www.w3.org/TR/html4/sgml/entities.html
+const OTHER: &[(&str, u32)] = &[
+    ("OElig", 338),
+    ("oelig", 339),
+    ("Scaron", 352),
+    ("scaron", 353),
+    ("Yuml", 376),
+    ("fnof", 402),
+    ("circ", 710),
+    ("tilde", 732),
+    ("thetasym", 977),
+    ("upsih", 978),
+    ("piv", 982),
+    ("ensp", 8194),
+    ("emsp", 8195),
+    ("thinsp", 8201),
+    ("zwnj", 8204),
+    ("zwj", 8205),
+    ("lrm", 8206),
+    ("rlm", 8207),
+    ("ndash", 8211),
+    ("mdash", 8212),
+    ("lsquo", 8216),
+    ("rsquo", 8217),
+    ("sbquo", 8218),
+    ("ldquo", 8220),
+    ("rdquo", 8221),
+    ("bdquo", 8222),
+    ("dagger", 8224),
+    ("Dagger", 8225),
+    ("bull", 8226),
+    ("hellip", 8230),
+    ("permil", 8240),
+    ("prime", 8242),
+    ("Prime", 8243),
+    ("lsaquo", 8249),
+    ("rsaquo", 8250),
+    ("oline", 8254),
+    ("frasl", 8260),
+    ("euro", 8364),
+    ("image", 8465),
+    ("weierp", 8472),
+    ("real", 8476),
+    ("trade", 8482),
+    ("alefsym", 8501),
+    ("larr", 8592),
+    ("uarr", 8593),
+    ("rarr", 8594),
+    ("darr", 8595),
+    ("harr", 8596),
+    ("crarr", 8629),
+    ("lArr", 8656),
+    ("uArr", 8657),
+    ("rArr", 8658),
+    ("dArr", 8659),
+    ("hArr", 8660),
+    ("forall", 8704),
+    ("part", 8706),
+    ("exist", 8707),
+    ("empty", 8709),
+    ("nabla", 8711),
+    ("isin", 8712),
+    ("notin", 8713),
+    ("ni", 8715),
+    ("prod", 8719),
+    ("sum", 8721),
+    ("minus", 8722),
+    ("lowast", 8727),
+    ("radic", 8730),
+    ("prop", 8733),
+    ("infin", 8734),
+    ("ang", 8736),
+    ("and", 8743),
+    ("or", 8744),
+    ("cap", 8745),
+    ("cup", 8746),
+    ("int", 8747),
+    ("there4", 8756),
+    ("sim", 8764),
+    ("cong", 8773),
+    ("asymp", 8776),
+    ("ne", 8800),
+    ("equiv", 8801),
+    ("le", 8804),
+    ("ge", 8805),
+    ("sub", 8834),
+    ("sup", 8835),
+    ("nsub", 8836),
+    ("sube", 8838),
+    ("supe", 8839),
+    ("oplus", 8853),
+    ("otimes", 8855),
+    ("perp", 8869),
+    ("sdot", 8901),
+    ("lceil", 8968),
+    ("rceil", 8969),
+    ("lfloor", 8970),
+    ("rfloor", 8971),
+    ("lang", 9001),
+    ("rang", 9002),
+    ("loz", 9674),
+    ("spades", 9824),
+    ("clubs", 9827),
+    ("hearts", 9829),
+    ("diams", 9830),
+];
+
+fn html_entity(name: &str) -> Option<char> {
+    if name.is_empty() {
+        return None;
+    }
+    let code = if let Some(index) = LATIN_1.iter().position(|n| *n == name) {
+        0xA0 + index as u32
+    } else if let Some(index) = GREEK_UPPER.iter().position(|n| *n == name) {
+        0x391 + index as u32
+    } else if let Some(index) = GREEK_LOWER.iter().position(|n| *n == name) {
+        0x3B1 + index as u32
+    } else {
+        OTHER
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, code)| *code)?
+    };
+    char::from_u32(code)
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+use std::io::ErrorKind;
+
+use crate::Span;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    // The name of the element left open and where it was opened
+    UnclosedTag(String, Span),
+    // The name of the element that was open, the name that closed it and where
+    MismatchedClose(String, String, Span),
+    // A closing tag with no element left open to close
+    UnexpectedClose(String, Span),
+    // Where the opening quote of the attribute value was found
+    UnterminatedAttribute(Span),
+    // Where an attribute value without quotes was found outside of HTML
+    UnquotedAttribute(Span),
+    // Where the lexer was when the input ran out mid node
+    UnexpectedEof(Span),
+    // A `]]>` found outside of a CDATA section
+    StrayCDataEnd(Span),
+    // Reading from the underlying reader failed, or it was not valid UTF-8
+    Io(ErrorKind, Span),
+}
+
+impl Error {
+    pub fn span(&self) -> &Span {
+        match self {
+            Error::UnclosedTag(_, span)
+            | Error::MismatchedClose(_, _, span)
+            | Error::UnexpectedClose(_, span)
+            | Error::UnterminatedAttribute(span)
+            | Error::UnquotedAttribute(span)
+            | Error::UnexpectedEof(span)
+            | Error::StrayCDataEnd(span)
+            | Error::Io(_, span) => span,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnclosedTag(name, span) => {
+                write!(f, "{span}: <{name}> was never closed")
+            }
+            Error::MismatchedClose(open, close, span) => {
+                write!(f, "{span}: expected </{open}> but found </{close}>")
+            }
+            Error::UnexpectedClose(close, span) => {
+                write!(f, "{span}: </{close}> does not close any open element")
+            }
+            Error::UnterminatedAttribute(span) => {
+                write!(f, "{span}: attribute value is missing its closing quote")
+            }
+            Error::UnquotedAttribute(span) => {
+                write!(f, "{span}: attribute value must be quoted")
+            }
+            Error::UnexpectedEof(span) => write!(f, "{span}: unexpected end of input"),
+            Error::StrayCDataEnd(span) => {
+                write!(f, "{span}: `]]>` found outside of a CDATA section")
+            }
+            Error::Io(kind, span) => write!(f, "{span}: could not read input: {kind}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,85 @@
+// The parts of HTML5 that let a document leave tags unclosed
+
+// Elements that never have content or an end tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+// The start of any of these ends an open `<p>`
+const CLOSES_PARAGRAPH: [&str; 30] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+pub(crate) fn is_void(name: &str) -> bool {
+    VOID_ELEMENTS.contains(&name)
+}
+
+// Whether `name` can be closed without an end tag, by the start of another
+// element or by the end of its parent
+pub(crate) fn has_optional_end(name: &str) -> bool {
+    matches!(
+        name,
+        "p" | "li"
+            | "dt"
+            | "dd"
+            | "option"
+            | "optgroup"
+            | "rt"
+            | "rp"
+            | "tr"
+            | "td"
+            | "th"
+            | "thead"
+            | "tbody"
+            | "tfoot"
+            | "colgroup"
+            | "caption"
+    )
+}
+
+// Whether the start of `opening` ends the element `open`
+pub(crate) fn closes(open: &str, opening: &str) -> bool {
+    match open {
+        "p" => CLOSES_PARAGRAPH.contains(&opening),
+        "li" => opening == "li",
+        "dt" | "dd" => matches!(opening, "dt" | "dd"),
+        "option" => matches!(opening, "option" | "optgroup"),
+        "optgroup" => opening == "optgroup",
+        "rt" | "rp" => matches!(opening, "rt" | "rp"),
+        "tr" => opening == "tr",
+        "td" | "th" => matches!(opening, "td" | "th" | "tr"),
+        "thead" | "tbody" | "tfoot" => matches!(opening, "tbody" | "tfoot"),
+        "colgroup" | "caption" => matches!(opening, "thead" | "tbody" | "tfoot" | "tr"),
+        _ => false,
+    }
+}
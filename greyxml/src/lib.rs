@@ -1,18 +1,47 @@
-use std::error::Error;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::iter::{Map, Peekable};
+use std::mem;
+use std::ops::Range;
+use std::str::Chars;
+
+mod document;
+mod encoding;
+mod entities;
+mod error;
+mod html;
+mod namespace;
+mod reader;
+mod writer;
+
+pub use document::{parse_document, Ast, Attribute, Document, Node, NodeId};
+pub use encoding::DecodingReader;
+use entities::decode;
+pub use entities::{decode_entities, Entities};
+pub use error::Error;
+use namespace::Namespaces;
+pub use namespace::{Name, XMLNS_NAMESPACE, XML_NAMESPACE};
+pub use reader::{Reader, StrReader};
+pub use writer::Writer;
 
 #[derive(Clone, Debug, PartialEq)]
-pub enum Lexicals {
+pub enum Lexicals<'a> {
     OpenArrow(Span),
     CloseArrow(Span),
-    ImmediateClose(Span),
     CloseFSlash(Span),
-    LeftSquareBracket(Span),
-    RightSquareBracket(Span),
-    Bang(Span),
     Equals(Span),
     DoubleQuote(Span),
-    Question(Span),
-    Text(String, Span),
+    SingleQuote(Span),
+    Text(Cow<'a, str>, Span),
+    // The content between `<![CDATA[` and `]]>`, exactly as written
+    CData(Cow<'a, str>, Span),
+    // The content between `<!--` and `-->`
+    Comment(Cow<'a, str>, Span),
+    // Everything between `<?` and `?>`
+    ProcessingInstruction(Cow<'a, str>, Span),
+    // Everything after `<!DOCTYPE` up to its closing `>`
+    Doctype(Cow<'a, str>, Span),
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -32,450 +61,939 @@ impl Span {
     }
 }
 
-pub fn lex(stream: &str) -> Result<Vec<Lexicals>, Box<dyn Error>> {
-    let mut lexed = vec![];
-    let mut peekable = stream.chars().peekable();
-    let mut in_block = false;
-    let mut in_q_block = false;
-    let mut in_simple_block = false;
-    let mut is_defining_node_name = false;
-    let mut in_quote = false;
-    let mut temp_string = String::new();
-    let mut current_span = Span::new();
-    while let Some(c) = peekable.peek() {
-        let c = *c;
-        peekable.next();
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.row, self.column)
+    }
+}
+
+// Lexes one character at a time, pulling from `chars` only as far as it needs
+// to look ahead. When lexing a string the text of each lexical is a slice of
+// it, otherwise it is copied out a character at a time.
+pub(crate) struct Lexer<'a, I: Iterator<Item = Result<char, Error>>> {
+    chars: Peekable<I>,
+    state: LexState<'a>,
+    finished: bool,
+}
+
+// The characters of a string, for lexing it without copying any text
+pub(crate) type StrChars<'a> = Map<Chars<'a>, fn(char) -> Result<char, Error>>;
+
+#[derive(Default)]
+struct LexState<'a> {
+    lexed: VecDeque<Lexicals<'a>>,
+    // Carries on past anything it can make sense of, handing back what it
+    // recovered from as errors in between the lexicals
+    lenient: bool,
+    warnings: VecDeque<Error>,
+    // The string being lexed, if the lexicals can borrow from it
+    source: Option<&'a str>,
+    // Byte offset of the character being stepped over
+    offset: usize,
+    in_block: bool,
+    // The quote character of the parameter value being read
+    quote: Option<char>,
+    // Where the currently open quote started, if it was opened inside a node
+    quote_span: Option<Span>,
+    // Markup being read verbatim, starting from where it was opened
+    raw: Option<Span>,
+    // The text read since the last lexical, as a range of the source
+    text: Range<usize>,
+    // The same text copied out, when there is no source to borrow from
+    temp_string: String,
+    current_span: Span,
+}
+
+impl<'a, I: Iterator<Item = Result<char, Error>>> Lexer<'a, I> {
+    pub(crate) fn new(chars: I) -> Self {
+        Lexer {
+            chars: chars.peekable(),
+            state: LexState {
+                current_span: Span::new(),
+                ..Default::default()
+            },
+            finished: false,
+        }
+    }
+}
+
+impl<'a> Lexer<'a, StrChars<'a>> {
+    pub(crate) fn borrowing(source: &'a str) -> Self {
+        let mut lexer = Lexer::new(source.chars().map(Ok as fn(char) -> Result<char, Error>));
+        lexer.state.source = Some(source);
+        lexer
+    }
+}
+
+impl<'a, I: Iterator<Item = Result<char, Error>>> Lexer<'a, I> {
+    pub(crate) fn lenient(mut self) -> Self {
+        self.state.lenient = true;
+        self
+    }
+}
+
+impl<'a, I: Iterator<Item = Result<char, Error>>> Iterator for Lexer<'a, I> {
+    type Item = Result<Lexicals<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(warning) = self.state.warnings.pop_front() {
+                return Some(Err(warning));
+            }
+            if let Some(lexical) = self.state.lexed.pop_front() {
+                return Some(Ok(lexical));
+            }
+            if self.finished {
+                return None;
+            }
+            let result = match self.chars.next() {
+                Some(Ok(c)) => {
+                    let result = self.state.step(c, &mut self.chars);
+                    self.state.offset += c.len_utf8();
+                    result
+                }
+                Some(Err(e)) => Err(e),
+                None => {
+                    self.finished = true;
+                    self.state.finish()
+                }
+            };
+            if let Err(e) = result {
+                self.finished = true;
+                self.state.lexed.clear();
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl<'a> LexState<'a> {
+    fn step<I>(&mut self, c: char, peekable: &mut Peekable<I>) -> Result<(), Error>
+    where
+        I: Iterator<Item = Result<char, Error>>,
+    {
+        if self.raw.is_some() {
+            return self.step_raw(c);
+        }
+        // Markup characters inside a parameter value are part of the value
+        if let Some(quote) = self.quote {
+            if c != quote && c != '\n' {
+                self.push(c);
+                self.current_span.length += 1;
+                return Ok(());
+            }
+        }
         match c {
             '<' => {
-                in_block = true;
-                if peekable.peek() == Some(&'?') {
-                    in_simple_block = true;
-                }
-                if !temp_string.is_empty() {
-                    current_span.length -= 2;
-                    temp_string = temp_string.trim_end_matches(" ").to_owned();
-                    if temp_string.ends_with('\n') {
-                        if current_span.row > 0 {
-                            current_span.row -= 1;
-                        }
+                if !self.text_is_empty() {
+                    self.current_span.length = self.current_span.length.saturating_sub(2);
+                    let text = self.take_text();
+                    let text = narrow(text, |text| text.trim_end_matches(' '));
+                    let ends_line = text.ends_with('\n');
+                    if ends_line && self.current_span.row > 0 {
+                        self.current_span.row -= 1;
                     }
-                    lexed.push(Lexicals::Text(temp_string.clone(), current_span.clone()));
-                    if temp_string.ends_with('\n') {
-                        current_span.column = 1;
-                        current_span.row += 1;
+                    self.lexed
+                        .push_back(Lexicals::Text(text, self.current_span.clone()));
+                    if ends_line {
+                        self.current_span.column = 1;
+                        self.current_span.row += 1;
                     } else {
-                        current_span.column += current_span.length;
+                        self.current_span.column += self.current_span.length;
                     }
-                    current_span.length = 0;
-                    temp_string = String::new();
+                    self.current_span.length = 0;
                 }
-                if peekable.peek() == Some(&'/') {
-                    current_span.length = 1;
-                    lexed.push(Lexicals::OpenArrow(current_span.clone()));
-                    current_span.column += current_span.length;
-                    lexed.push(Lexicals::CloseFSlash(current_span.clone()));
-                    current_span.column += current_span.length;
-                    current_span.length = 0;
-                    peekable.next();
-                } else {
-                    current_span.length = 1;
-                    lexed.push(Lexicals::OpenArrow(current_span.clone()));
-                    current_span.column += current_span.length;
-                    current_span.length = 0;
+                if let Some(Ok('!' | '?')) = peekable.peek() {
+                    // CDATA, comments, processing instructions and declarations
+                    // are read as they are up to whatever ends them
+                    self.raw = Some(self.current_span.clone());
+                    self.current_span.column += 1;
+                    return Ok(());
                 }
-                is_defining_node_name = true;
-            }
-            '?' => {
-                if peekable.peek() == Some(&'>') {
-                    in_simple_block = false;
-                    lexed.push(Lexicals::ImmediateClose(current_span.clone()));
-                    current_span.column += current_span.length;
-                    current_span.length = 0;
+                self.in_block = true;
+                if peekable.peek() == Some(&Ok('/')) {
+                    self.current_span.length = 1;
+                    self.lexed
+                        .push_back(Lexicals::OpenArrow(self.current_span.clone()));
+                    self.current_span.column += self.current_span.length;
+                    self.lexed
+                        .push_back(Lexicals::CloseFSlash(self.current_span.clone()));
+                    self.current_span.column += self.current_span.length;
+                    self.current_span.length = 0;
                     peekable.next();
+                    self.offset += 1;
                 } else {
-                    lexed.push(Lexicals::Question(current_span.clone()));
-                    current_span.column += current_span.length;
-                    current_span.length = 0;
+                    self.current_span.length = 1;
+                    self.lexed
+                        .push_back(Lexicals::OpenArrow(self.current_span.clone()));
+                    self.current_span.column += self.current_span.length;
+                    self.current_span.length = 0;
                 }
             }
-            '>' => {
-                if in_block {
-                    if !temp_string.is_empty() {
-                        temp_string = temp_string.trim_end_matches(" ").to_owned();
-                        if temp_string.ends_with('\n') {
-                            if current_span.row > 1 {
-                                current_span.row -= 1;
-                            }
-                        }
-                        current_span.length -= 1;
-                        lexed.push(Lexicals::Text(temp_string.clone(), current_span.clone()));
-                        if temp_string.ends_with('\n') {
-                            current_span.column = 1;
-                            current_span.row += 1;
-                        } else {
-                            current_span.column += current_span.length;
-                        }
-                        current_span.length = 1;
-                        temp_string = String::new();
+            '>' if !self.in_block => {
+                if self.text().ends_with("]]") {
+                    let e = Error::StrayCDataEnd(Span {
+                        column: (self.current_span.column + self.current_span.length)
+                            .saturating_sub(3),
+                        length: 3,
+                        ..self.current_span.clone()
+                    });
+                    if !self.lenient {
+                        return Err(e);
                     }
-                    in_block = false;
+                    // Kept as the text it was written as
+                    self.warnings.push_back(e);
                 }
-                lexed.push(Lexicals::CloseArrow(current_span.clone()));
-                current_span.column += current_span.length;
-                current_span.length = 0;
-                is_defining_node_name = false;
+                // Outside of a node it is just text
+                self.push(c);
             }
-            '[' => {
-                if in_block {
-                    in_block = false;
-                    in_q_block = true;
-                }
-                if !temp_string.is_empty() {
-                    temp_string = temp_string.trim_end_matches(" ").to_owned();
-                    if temp_string.ends_with('\n') {
-                        if current_span.row > 0 {
-                            current_span.row -= 1;
-                        }
+            '>' => {
+                if !self.text_is_empty() {
+                    let text = self.take_text();
+                    let text = narrow(text, |text| text.trim_end_matches(' '));
+                    let ends_line = text.ends_with('\n');
+                    if ends_line && self.current_span.row > 1 {
+                        self.current_span.row -= 1;
                     }
-                    current_span.length -= 1;
-                    lexed.push(Lexicals::Text(temp_string.clone(), current_span.clone()));
-                    if temp_string.ends_with('\n') {
-                        current_span.column = 1;
-                        current_span.row += 1;
+                    self.current_span.length -= 1;
+                    self.lexed
+                        .push_back(Lexicals::Text(text, self.current_span.clone()));
+                    if ends_line {
+                        self.current_span.column = 1;
+                        self.current_span.row += 1;
                     } else {
-                        current_span.column += current_span.length;
+                        self.current_span.column += self.current_span.length;
                     }
-                    current_span.length = 1;
-                    temp_string = String::new();
-                }
-                lexed.push(Lexicals::LeftSquareBracket(current_span.clone()));
-                current_span.length = 0;
-            }
-            ']' => {
-                if in_q_block {
-                    in_q_block = false;
-                    in_block = true;
-                }
-                if peekable.peek() == Some(&'>') || peekable.peek() == Some(&']') {
-                    if !temp_string.is_empty() {
-                        temp_string = temp_string.trim_end_matches(" ").to_owned();
-                        if temp_string.ends_with('\n') {
-                            if current_span.row > 0 {
-                                current_span.row -= 1;
-                            }
-                        }
-                        current_span.length -= 1;
-                        lexed.push(Lexicals::Text(temp_string.clone(), current_span.clone()));
-                        if temp_string.ends_with('\n') {
-                            current_span.column = 1;
-                            current_span.row += 1;
-                        } else {
-                            current_span.column += current_span.length;
-                        }
-                        current_span.length = 1;
-                        temp_string = String::new();
-                    }
-                    lexed.push(Lexicals::RightSquareBracket(current_span.clone()));
-                    current_span.length = 0;
-                } else {
-                    temp_string.push(c);
-                }
-            }
-            '!' => {
-                if in_block || in_q_block || in_simple_block {
-                    lexed.push(Lexicals::Bang(current_span.clone()));
-                    current_span.column += current_span.length;
-                    current_span.length = 0;
-                } else {
-                    temp_string.push(c);
+                    self.current_span.length = 1;
                 }
+                self.in_block = false;
+                self.lexed
+                    .push_back(Lexicals::CloseArrow(self.current_span.clone()));
+                self.current_span.column += self.current_span.length;
+                self.current_span.length = 0;
             }
             '=' => {
-                if in_quote {
-                    temp_string.push(c);
-                    continue;
-                }
-                if (in_block || in_q_block || in_simple_block) && !temp_string.is_empty() {
-                    temp_string = temp_string.trim_end_matches(" ").to_owned();
-                    if temp_string.ends_with('\n') {
-                        if current_span.row > 0 {
-                            current_span.row -= 1;
-                        }
+                if self.in_block && !self.text_is_empty() {
+                    let text = self.take_text();
+                    let text = narrow(text, |text| text.trim_end_matches(' '));
+                    let ends_line = text.ends_with('\n');
+                    if ends_line && self.current_span.row > 0 {
+                        self.current_span.row -= 1;
                     }
-                    current_span.length -= 1;
-                    lexed.push(Lexicals::Text(temp_string.clone(), current_span.clone()));
-                    if temp_string.ends_with('\n') {
-                        current_span.column = 1;
-                        current_span.row += 1;
+                    self.current_span.length -= 1;
+                    self.lexed
+                        .push_back(Lexicals::Text(text, self.current_span.clone()));
+                    if ends_line {
+                        self.current_span.column = 1;
+                        self.current_span.row += 1;
                     } else {
-                        current_span.column += current_span.length;
+                        self.current_span.column += self.current_span.length;
                     }
-                    temp_string = String::new();
-                    current_span.length = 1;
-                    lexed.push(Lexicals::Equals(current_span.clone()));
-                    current_span.column += current_span.length;
-                    current_span.length = 0;
+                    self.current_span.length = 1;
+                    self.lexed
+                        .push_back(Lexicals::Equals(self.current_span.clone()));
+                    self.current_span.column += self.current_span.length;
+                    self.current_span.length = 0;
                 } else {
-                    temp_string.push(c);
+                    self.push(c);
                 }
             }
-            '"' => {
-                in_quote = !in_quote;
-                if (in_block || in_simple_block) && !temp_string.is_empty() {
-                    temp_string = temp_string.trim_end_matches(" ").to_owned();
-                    if temp_string.ends_with('\n') {
-                        if current_span.row > 0 {
-                            current_span.row -= 1;
-                        }
+            '"' | '\'' if !self.in_block => self.push(c),
+            '"' | '\'' => {
+                self.quote = match self.quote {
+                    Some(_) => None,
+                    None => Some(c),
+                };
+                self.quote_span = self.quote.map(|_| self.current_span.clone());
+                if !self.text_is_empty() {
+                    let text = self.take_text();
+                    let text = narrow(text, |text| text.trim_end_matches(' '));
+                    let ends_line = text.ends_with('\n');
+                    if ends_line && self.current_span.row > 0 {
+                        self.current_span.row -= 1;
                     }
-                    current_span.length -= 1;
-                    lexed.push(Lexicals::Text(temp_string.clone(), current_span.clone()));
-                    if temp_string.ends_with('\n') {
-                        current_span.column = 1;
-                        current_span.row += 1;
+                    self.current_span.length -= 1;
+                    self.lexed
+                        .push_back(Lexicals::Text(text, self.current_span.clone()));
+                    if ends_line {
+                        self.current_span.column = 1;
+                        self.current_span.row += 1;
                     } else {
-                        current_span.column += current_span.length;
+                        self.current_span.column += self.current_span.length;
                     }
-                    temp_string = String::new();
-                    current_span.length = 1;
+                    self.current_span.length = 1;
                 }
-                lexed.push(Lexicals::DoubleQuote(current_span.clone()));
-                current_span.column += current_span.length;
-                current_span.length = 0;
+                self.lexed.push_back(if c == '"' {
+                    Lexicals::DoubleQuote(self.current_span.clone())
+                } else {
+                    Lexicals::SingleQuote(self.current_span.clone())
+                });
+                self.current_span.column += self.current_span.length;
+                self.current_span.length = 0;
             }
             '/' => {
-                if !in_quote && (in_block || in_simple_block) {
-                    lexed.push(Lexicals::CloseFSlash(current_span.clone()));
-                    current_span.column += current_span.length;
-                    current_span.length = 0;
+                // Anywhere else it is part of a name or unquoted value, like `href=/a/b`
+                if self.in_block && peekable.peek() == Some(&Ok('>')) {
+                    // The name before a `/>` is written straight up against it
+                    self.push_text();
+                    self.lexed
+                        .push_back(Lexicals::CloseFSlash(self.current_span.clone()));
+                    self.current_span.column += self.current_span.length;
+                    self.current_span.length = 0;
                 } else {
-                    temp_string.push(c);
+                    self.push(c);
                 }
             }
-            /*
-            '?' => {
-                lexed.push(Lexicals::Question);
-                in_q_block = !in_q_block;
-            },
-            */
+            '\n' if self.in_block && self.quote.is_none() => {
+                self.push_text();
+                self.current_span.row += 1;
+                self.current_span.column = 1;
+            }
             '\n' => {
-                if !temp_string.is_empty() {
-                    temp_string.push('\n');
+                if !self.text_is_empty() {
+                    self.push('\n');
                 } else {
-                    current_span.row += 1;
-                    current_span.column = 1;
+                    self.current_span.row += 1;
+                    self.current_span.column = 1;
                 }
             }
-            _ => {
-                if in_quote {
-                    temp_string.push(c);
-                    current_span.length += 1;
-                    continue;
-                }
-                if c == ' ' {
-                    if !is_defining_node_name {
-                        temp_string.push(c);
-                    } else {
-                        if !temp_string.is_empty() {
-                            temp_string = temp_string.trim_end_matches(" ").to_owned();
-                            if temp_string.ends_with('\n') {
-                                if current_span.row > 0 {
-                                    current_span.row -= 1;
-                                }
-                            }
-                            current_span.length -= 1;
-                            lexed.push(Lexicals::Text(temp_string.clone(), current_span.clone()));
-                            if temp_string.ends_with('\n') {
-                                current_span.column = 1;
-                                current_span.row += 1;
-                            } else {
-                                current_span.column += current_span.length;
-                            }
-                            temp_string = String::new();
-                            current_span.length = 0;
-                        }
-                        is_defining_node_name = false;
-                    }
-                } else {
-                    temp_string.push(c);
-                }
+            // Whitespace inside a node separates its name and parameters
+            ' ' | '\t' | '\r' if self.in_block => self.push_text(),
+            _ => self.push(c),
+        }
+        self.current_span.length += 1;
+        Ok(())
+    }
+
+    // Adds `c`, the character being stepped over, to the text being read.
+    // Nothing is ever left out from the middle of it, so it is always one
+    // range of the source.
+    fn push(&mut self, c: char) {
+        if self.source.is_none() {
+            self.temp_string.push(c);
+            return;
+        }
+        if self.text.is_empty() {
+            self.text.start = self.offset;
+        }
+        self.text.end = self.offset + c.len_utf8();
+    }
+
+    fn text(&self) -> &str {
+        match self.source {
+            Some(source) => &source[self.text.clone()],
+            None => &self.temp_string,
+        }
+    }
+
+    fn text_is_empty(&self) -> bool {
+        self.text.is_empty() && self.temp_string.is_empty()
+    }
+
+    fn take_text(&mut self) -> Cow<'a, str> {
+        match self.source {
+            Some(source) => Cow::Borrowed(&source[mem::take(&mut self.text)]),
+            None => Cow::Owned(mem::take(&mut self.temp_string)),
+        }
+    }
+
+    fn push_text(&mut self) {
+        if self.text_is_empty() {
+            return;
+        }
+        let text = self.take_text();
+        let text = narrow(text, |text| text.trim_end_matches(' '));
+        let ends_line = text.ends_with('\n');
+        if ends_line && self.current_span.row > 0 {
+            self.current_span.row -= 1;
+        }
+        self.current_span.length = self.current_span.length.saturating_sub(1);
+        self.lexed
+            .push_back(Lexicals::Text(text, self.current_span.clone()));
+        if ends_line {
+            self.current_span.column = 1;
+            self.current_span.row += 1;
+        } else {
+            self.current_span.column += self.current_span.length;
+        }
+        self.current_span.length = 0;
+    }
+
+    // Reads one character of markup that is taken as it is written
+    fn step_raw(&mut self, c: char) -> Result<(), Error> {
+        let Some(span) = &self.raw else {
+            return Ok(());
+        };
+        let span = span.clone();
+        self.push(c);
+        if c == '\n' {
+            self.current_span.row += 1;
+            self.current_span.column = 1;
+        } else {
+            self.current_span.column += 1;
+        }
+        if !markup_ended(self.text()) {
+            return Ok(());
+        }
+        let span = Span {
+            length: self.text().chars().count() + 1,
+            ..span
+        };
+        let raw = self.take_text();
+        if let Some(lexical) = markup_lexical(raw, span) {
+            self.lexed.push_back(lexical);
+        }
+        self.raw = None;
+        // Left as it would be after any other `>`
+        self.current_span.length = 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        if let Some(span) = self.quote_span.take() {
+            return Err(Error::UnterminatedAttribute(span));
+        }
+        if let Some(span) = self.raw.take() {
+            return Err(Error::UnexpectedEof(span));
+        }
+        if self.in_block {
+            return Err(Error::UnexpectedEof(self.current_span.clone()));
+        }
+        // Text after the last node, as a fragment of HTML can have
+        self.push_text();
+        Ok(())
+    }
+}
+
+// Narrows `text` down to the part of it `slice` picks out, only copying it if
+// it was already owned and has changed
+fn narrow<'a>(text: Cow<'a, str>, slice: impl for<'b> Fn(&'b str) -> &'b str) -> Cow<'a, str> {
+    match text {
+        Cow::Borrowed(text) => Cow::Borrowed(slice(text)),
+        Cow::Owned(text) => {
+            let sliced = slice(&text);
+            if sliced.len() == text.len() {
+                Cow::Owned(text)
+            } else {
+                Cow::Owned(sliced.to_owned())
             }
         }
-        current_span.length += 1;
     }
-    Ok(lexed)
 }
 
-#[derive(Debug)]
-pub enum Ast {
-    // Takes an ElementNode
-    DescriptorNode(Box<Tokens>),
-    // Has a name, a series of attributes, and any child Nodes
-    ElementNode(String, Vec<Box<Tokens>>, Vec<Box<Tokens>>),
-    // An attribute is the attribute name and a TextNode
-    Attribute(String, Box<Tokens>),
-    TextNode(String),
+// Whether `raw`, everything after a `<!` or `<?`'s `<`, has reached the end
+// of its markup
+fn markup_ended(raw: &str) -> bool {
+    // Not yet enough to know what kind of markup it is
+    if "![CDATA[".starts_with(raw) || "!--".starts_with(raw) {
+        return false;
+    }
+    if let Some(cdata) = raw.strip_prefix("![CDATA[") {
+        return cdata.ends_with("]]>");
+    }
+    if let Some(comment) = raw.strip_prefix("!--") {
+        return comment.ends_with("-->");
+    }
+    if let Some(instruction) = raw.strip_prefix('?') {
+        return instruction.ends_with("?>");
+    }
+    let Some(declaration) = raw.strip_suffix('>') else {
+        return false;
+    };
+    // A `>` inside quotes or a DOCTYPE's internal subset doesn't end it
+    let mut quote = None;
+    let mut depth = 0usize;
+    for c in declaration.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    quote.is_none() && depth == 0
 }
 
+// Declarations other than DOCTYPE are only allowed inside one, so are dropped
+fn markup_lexical(raw: Cow<'_, str>, span: Span) -> Option<Lexicals<'_>> {
+    if raw.starts_with("![CDATA[") {
+        let cdata = narrow(raw, |raw| {
+            let cdata = &raw["![CDATA[".len()..];
+            cdata.strip_suffix("]]>").unwrap_or(cdata)
+        });
+        return Some(Lexicals::CData(cdata, span));
+    }
+    if raw.starts_with("!--") {
+        let comment = narrow(raw, |raw| {
+            let comment = &raw["!--".len()..];
+            comment.strip_suffix("-->").unwrap_or(comment)
+        });
+        return Some(Lexicals::Comment(comment, span));
+    }
+    if raw.starts_with('?') {
+        let instruction = narrow(raw, |raw| {
+            let instruction = &raw[1..];
+            instruction.strip_suffix("?>").unwrap_or(instruction)
+        });
+        return Some(Lexicals::ProcessingInstruction(instruction, span));
+    }
+    let declaration = raw.strip_prefix('!')?.strip_suffix('>')?;
+    let keyword = declaration.get(..7)?;
+    if !keyword.eq_ignore_ascii_case("DOCTYPE") {
+        return None;
+    }
+    let doctype = narrow(raw, |raw| raw[1 + "DOCTYPE".len()..raw.len() - 1].trim());
+    Some(Lexicals::Doctype(doctype, span))
+}
+
+pub fn lex(stream: &str) -> Result<Vec<Lexicals<'_>>, Error> {
+    Lexer::borrowing(stream).collect()
+}
+
+// Text borrows from the string that was tokenized wherever it can, it is
+// only owned when decoding entities changed it or it was read from a stream
 #[derive(Clone, Debug, PartialEq)]
-pub enum Tokens {
-    // A single line node, such as the xml version info
-    SimpleNode(String),
-    OpenNode(String),
-    ParameterName(String),
-    ParameterValue(String),
-    CloseNode(String),
-    Text(String),
+pub enum Tokens<'a> {
+    OpenNode(Name<'a>),
+    ParameterName(Name<'a>),
+    ParameterValue(Cow<'a, str>),
+    CloseNode(Name<'a>),
+    Text(Cow<'a, str>),
+    // Text from a CDATA section, none of it is decoded or trimmed
+    CData(Cow<'a, str>),
+    Comment(Cow<'a, str>),
+    // The target, such as `xml` for the xml version info, and everything after it
+    ProcessingInstruction(Cow<'a, str>, Cow<'a, str>),
+    // Everything after `<!DOCTYPE`, such as `html`
+    Doctype(Cow<'a, str>),
+}
+
+impl Tokens<'_> {
+    // Copies out anything borrowed, for keeping the token after the input is gone
+    pub fn into_owned(self) -> Tokens<'static> {
+        let owned = |text: Cow<str>| Cow::Owned(text.into_owned());
+        match self {
+            Tokens::OpenNode(name) => Tokens::OpenNode(name.into_owned()),
+            Tokens::ParameterName(name) => Tokens::ParameterName(name.into_owned()),
+            Tokens::ParameterValue(value) => Tokens::ParameterValue(owned(value)),
+            Tokens::CloseNode(name) => Tokens::CloseNode(name.into_owned()),
+            Tokens::Text(text) => Tokens::Text(owned(text)),
+            Tokens::CData(text) => Tokens::CData(owned(text)),
+            Tokens::Comment(text) => Tokens::Comment(owned(text)),
+            Tokens::ProcessingInstruction(target, content) => {
+                Tokens::ProcessingInstruction(owned(target), owned(content))
+            }
+            Tokens::Doctype(doctype) => Tokens::Doctype(owned(doctype)),
+        }
+    }
 }
 
-pub fn tokenize<T: Sized>(lexed: &mut T) -> Result<Vec<Tokens>, Box<dyn Error>>
+pub fn tokenize<'a, T>(lexed: &mut T) -> Result<Vec<Tokens<'a>>, Error>
 where
-    T: Iterator<Item = Lexicals>,
+    T: Iterator<Item = Lexicals<'a>>,
 {
-    let mut tokens = vec![];
-    let mut peekable = lexed.peekable();
-    let mut in_simple_node = false;
-    let mut in_node = false;
-    let mut in_close_node = false;
-    let mut is_param_value = false;
-    let mut node_names: Vec<String> = vec![];
-    let mut current_node = String::new();
-    while let Some(lx) = peekable.peek() {
-        let lex = lx.to_owned();
-        peekable.next();
+    tokenize_with_entities(lexed, Entities::Xml)
+}
+
+// Text and parameter values have their entity and character references
+// decoded, `entities` picks which named entities are understood. Node and
+// parameter names have their namespace resolved from the `xmlns` in scope.
+pub fn tokenize_with_entities<'a, T>(
+    lexed: &mut T,
+    entities: Entities,
+) -> Result<Vec<Tokens<'a>>, Error>
+where
+    T: Iterator<Item = Lexicals<'a>>,
+{
+    Tokenizer::new(lexed.map(Ok), entities).collect()
+}
+
+// Tokenizes HTML, such as the description of a feed item. HTML entities are
+// understood and parameter values don't need to be quoted.
+pub fn tokenize_html<'a, T>(lexed: &mut T) -> Result<Vec<Tokens<'a>>, Error>
+where
+    T: Iterator<Item = Lexicals<'a>>,
+{
+    Tokenizer::html(lexed.map(Ok)).collect()
+}
+
+// Turns lexicals into tokens as they are pulled, holding on to a node only
+// until all of its parameters have been seen so its namespace can be resolved
+pub(crate) struct Tokenizer<'a, I: Iterator<Item = Result<Lexicals<'a>, Error>>> {
+    lexed: Peekable<I>,
+    state: TokenState<'a>,
+    namespaces: Namespaces<'a>,
+    finished: bool,
+    // Where the name of the last OpenNode handed out starts
+    span: Option<Span>,
+}
+
+struct TokenState<'a> {
+    tokens: VecDeque<Tokens<'a>>,
+    // Where each OpenNode in `tokens` starts, in the same order
+    open_spans: VecDeque<Span>,
+    entities: Entities,
+    // Allows parameter values without quotes, and closes void elements and
+    // elements whose end tag was left out
+    html: bool,
+    // Recovers from malformed XML rather than stopping at the first mistake,
+    // keeping each mistake in `warnings`
+    lenient: bool,
+    warnings: Vec<Error>,
+    in_node: bool,
+    in_close_node: bool,
+    is_param_value: bool,
+    is_quoted: bool,
+    node_names: Vec<(Cow<'a, str>, Span)>,
+    // Whether the node being read has its name yet, any text after it is a parameter
+    named: bool,
+}
+
+impl<'a, I: Iterator<Item = Result<Lexicals<'a>, Error>>> Tokenizer<'a, I> {
+    pub(crate) fn new(lexed: I, entities: Entities) -> Self {
+        Tokenizer {
+            lexed: lexed.peekable(),
+            state: TokenState {
+                tokens: VecDeque::new(),
+                open_spans: VecDeque::new(),
+                entities,
+                html: false,
+                lenient: false,
+                warnings: vec![],
+                in_node: false,
+                in_close_node: false,
+                is_param_value: false,
+                is_quoted: false,
+                node_names: vec![],
+                named: false,
+            },
+            namespaces: Namespaces::default(),
+            finished: false,
+            span: None,
+        }
+    }
+
+    pub(crate) fn html(lexed: I) -> Self {
+        let mut tokenizer = Tokenizer::new(lexed, Entities::Html);
+        tokenizer.state.html = true;
+        tokenizer
+    }
+
+    // Reads XML as it is found in the wild. End tags close anything left open
+    // inside their element, stray end tags are dropped, unquoted parameter
+    // values are accepted and a document that stops short is closed off.
+    // Errors from the lexer are kept as warnings too, unless reading failed.
+    pub(crate) fn lenient(lexed: I, entities: Entities) -> Self {
+        let mut tokenizer = Tokenizer::new(lexed, entities);
+        tokenizer.state.lenient = true;
+        tokenizer
+    }
+
+    // What was recovered from so far, only ever added to when lenient
+    pub(crate) fn warnings(&self) -> &[Error] {
+        &self.state.warnings
+    }
+
+    pub(crate) fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    // The number of tokens at the front of the queue making up an OpenNode and
+    // its parameters, if they have all been tokenized
+    fn open_node_length(&self) -> Option<usize> {
+        let length = self
+            .state
+            .tokens
+            .iter()
+            .skip(1)
+            .position(|t| !matches!(t, Tokens::ParameterName(_) | Tokens::ParameterValue(_)))
+            .map(|position| position + 1);
+        if self.finished {
+            length.or(Some(self.state.tokens.len()))
+        } else {
+            length
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = Result<Lexicals<'a>, Error>>> Iterator for Tokenizer<'a, I> {
+    type Item = Result<Tokens<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.state.tokens.front() {
+                Some(Tokens::OpenNode(_)) => {
+                    if let Some(length) = self.open_node_length() {
+                        let open_node = &mut self.state.tokens.make_contiguous()[..length];
+                        self.namespaces.open(open_node);
+                        self.span = self.state.open_spans.pop_front();
+                        return self.state.tokens.pop_front().map(Ok);
+                    }
+                }
+                Some(Tokens::CloseNode(_)) => {
+                    let mut token = self.state.tokens.pop_front();
+                    if let Some(Tokens::CloseNode(name)) = &mut token {
+                        self.namespaces.close(name);
+                    }
+                    return token.map(Ok);
+                }
+                Some(_) => return self.state.tokens.pop_front().map(Ok),
+                None if self.finished => return None,
+                None => {}
+            }
+            let result = match self.lexed.next() {
+                Some(Ok(lex)) => self.state.step(lex, &mut self.lexed),
+                Some(Err(e)) if self.state.lenient && !matches!(e, Error::Io(..)) => {
+                    self.state.warnings.push(e);
+                    Ok(())
+                }
+                Some(Err(e)) => Err(e),
+                None => {
+                    self.finished = true;
+                    self.state.finish()
+                }
+            };
+            if let Err(e) = result {
+                self.finished = true;
+                self.state.tokens.clear();
+                self.state.open_spans.clear();
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl<'a> TokenState<'a> {
+    fn step<I>(&mut self, lex: Lexicals<'a>, peekable: &mut Peekable<I>) -> Result<(), Error>
+    where
+        I: Iterator<Item = Result<Lexicals<'a>, Error>>,
+    {
         match lex {
             Lexicals::OpenArrow(_) => {
-                if let Some(lex) = peekable.peek() {
+                if let Some(Ok(lex)) = peekable.peek() {
                     match lex {
-                        Lexicals::Question(_) => in_simple_node = true,
                         Lexicals::CloseFSlash(_) => {}
                         _ => {
-                            in_node = true;
+                            self.in_node = true;
                         }
                     }
                 }
             }
             Lexicals::CloseArrow(_) => {
-                current_node = String::new();
-                in_close_node = false;
-                in_node = false;
-                in_simple_node = false;
-            }
-            Lexicals::ImmediateClose(_) => {
-                in_simple_node = false;
-                node_names.pop();
+                if self.html && self.in_node && self.named {
+                    if let Some((name, _)) = self.node_names.last() {
+                        if html::is_void(name) {
+                            self.close_to(self.node_names.len() - 1);
+                        }
+                    }
+                }
+                self.named = false;
+                self.in_close_node = false;
+                self.in_node = false;
             }
             Lexicals::CloseFSlash(_) => {
-                if in_node {
-                    in_node = false;
-                    if let Some(name) = node_names.pop() {
-                        current_node = name.clone();
-                        tokens.push(Tokens::CloseNode(name));
-                    } else {
-                        current_node = String::new();
+                if self.in_node {
+                    self.in_node = false;
+                    if let Some((name, _)) = self.node_names.pop() {
+                        self.tokens.push_back(Tokens::CloseNode(Name::new(name)));
                     }
                 } else {
-                    in_close_node = true;
+                    self.in_close_node = true;
                 }
             }
-            Lexicals::LeftSquareBracket(_) => {}
-            Lexicals::RightSquareBracket(_) => {
-                if let Some(Lexicals::CloseArrow(_)) = peekable.peek() {
-                    peekable.next();
+            Lexicals::Equals(_) => {
+                if self.in_node {
+                    self.is_param_value = true;
                 }
             }
-            Lexicals::Bang(_) => {
-                if let Some(Lexicals::LeftSquareBracket(_)) = peekable.peek() {
-                } else {
-                    in_simple_node = true;
-                }
-                if in_node {
-                    in_node = false;
-                    current_node = String::new();
+            Lexicals::DoubleQuote(_) | Lexicals::SingleQuote(_) => {
+                if self.is_param_value {
+                    // An empty value has no Text between its quotes
+                    if let Some(Ok(next)) = peekable.peek() {
+                        if mem::discriminant(next) == mem::discriminant(&lex) {
+                            peekable.next();
+                            self.tokens
+                                .push_back(Tokens::ParameterValue(Cow::Borrowed("")));
+                            self.is_param_value = false;
+                            return Ok(());
+                        }
+                    }
+                    self.is_quoted = true;
                 }
             }
-            Lexicals::Equals(_) => {
-                if in_node || in_simple_node {
-                    is_param_value = true;
-                }
+            Lexicals::CData(text, _) => self.tokens.push_back(Tokens::CData(text)),
+            Lexicals::Comment(text, _) => self.tokens.push_back(Tokens::Comment(text)),
+            Lexicals::ProcessingInstruction(instruction, _) => {
+                let target = narrow(instruction.clone(), |instruction| {
+                    instruction
+                        .split_once(char::is_whitespace)
+                        .map_or(instruction, |(target, _)| target)
+                });
+                let content = narrow(instruction, |instruction| {
+                    instruction
+                        .split_once(char::is_whitespace)
+                        .map_or("", |(_, content)| content.trim())
+                });
+                self.tokens
+                    .push_back(Tokens::ProcessingInstruction(target, content));
             }
-            Lexicals::DoubleQuote(_) => {}
-            Lexicals::Question(_) => {}
-            Lexicals::Text(text, _) => {
-                let trimmed = text.trim();
-                if in_node {
-                    if let Some(lex) = peekable.peek() {
-                        match lex {
-                            Lexicals::Equals(_) => {
-                                tokens.push(Tokens::ParameterName(trimmed.into()));
-                                continue;
-                            }
-                            _ => {
-                                if is_param_value {
-                                    tokens.push(Tokens::ParameterValue(trimmed.into()));
-                                    is_param_value = false;
-                                    continue;
-                                }
-                                if let Some(last) = node_names.last() {
-                                    if *last == current_node && *last != trimmed {
-                                        tokens.push(Tokens::ParameterName(trimmed.into()));
-                                        tokens.push(Tokens::ParameterValue("true".into()));
-                                        is_param_value = false;
-                                        continue;
-                                    }
-                                }
-                            }
+            Lexicals::Doctype(doctype, _) => self.tokens.push_back(Tokens::Doctype(doctype)),
+            Lexicals::Text(text, span) => {
+                let trimmed = narrow(text, str::trim);
+                if self.in_node {
+                    if let Some(Ok(Lexicals::Equals(_))) = peekable.peek() {
+                        let name = self.name(trimmed);
+                        self.tokens
+                            .push_back(Tokens::ParameterName(Name::new(name)));
+                        return Ok(());
+                    }
+                    if self.is_param_value {
+                        if !self.is_quoted && !self.html {
+                            self.recover(Error::UnquotedAttribute(span))?;
                         }
+                        self.tokens
+                            .push_back(Tokens::ParameterValue(decode(trimmed, self.entities)));
+                        self.is_param_value = false;
+                        self.is_quoted = false;
+                        return Ok(());
+                    }
+                    // A parameter without a value, such as `<input disabled>`
+                    if self.named {
+                        let name = self.name(trimmed);
+                        self.tokens
+                            .push_back(Tokens::ParameterName(Name::new(name)));
+                        self.tokens
+                            .push_back(Tokens::ParameterValue(Cow::Borrowed("")));
+                        return Ok(());
+                    }
+                    let name = self.name(trimmed);
+                    if self.html {
+                        self.close_implied(&name);
                     }
-                    node_names.push(trimmed.into());
-                    current_node = trimmed.into();
-                    tokens.push(Tokens::OpenNode(trimmed.into()));
-                    continue;
+                    self.node_names.push((name.clone(), span.clone()));
+                    self.open_spans.push_back(span);
+                    self.named = true;
+                    self.tokens.push_back(Tokens::OpenNode(Name::new(name)));
+                    return Ok(());
                 }
-                if in_close_node {
-                    tokens.push(Tokens::CloseNode(trimmed.into()));
-                    if let Some(popped) = node_names.pop() {
-                        current_node = popped;
-                    } else {
-                        current_node = String::new();
+                if self.in_close_node && self.html {
+                    // Anything left open inside the element is closed along with it, and
+                    // an end tag with nothing to close is dropped
+                    let name = self.name(trimmed);
+                    if let Some(depth) = self.node_names.iter().rposition(|(open, _)| *open == name)
+                    {
+                        self.close_to(depth);
                     }
-                    continue;
+                    return Ok(());
                 }
-                if in_simple_node {
-                    if let Some(lex) = peekable.peek() {
-                        match lex {
-                            Lexicals::Equals(_) => {
-                                tokens.push(Tokens::ParameterName(trimmed.into()));
-                                continue;
-                            }
-                            _ => {
-                                if is_param_value {
-                                    tokens.push(Tokens::ParameterValue(trimmed.into()));
-                                    is_param_value = false;
-                                    continue;
-                                }
-                                if let Some(last) = node_names.last() {
-                                    if *last == current_node {
-                                        tokens.push(Tokens::ParameterName(trimmed.into()));
-                                        tokens.push(Tokens::ParameterValue("true".into()));
-                                        is_param_value = false;
-                                        continue;
-                                    }
-                                }
-                            }
+                if self.in_close_node && self.lenient {
+                    match self
+                        .node_names
+                        .iter()
+                        .rposition(|(open, _)| *open == trimmed)
+                    {
+                        Some(depth) => {
+                            self.close_unclosed(depth + 1);
+                            self.close_to(depth);
                         }
+                        None => self
+                            .warnings
+                            .push(Error::UnexpectedClose(trimmed.into_owned(), span)),
                     }
-                    current_node = trimmed.into();
-                    tokens.push(Tokens::SimpleNode(trimmed.into()));
-                    node_names.push(trimmed.into());
-                    continue;
+                    return Ok(());
+                }
+                if self.in_close_node {
+                    match self.node_names.pop() {
+                        Some((open, _)) if open != trimmed => {
+                            return Err(Error::MismatchedClose(
+                                open.into_owned(),
+                                trimmed.into_owned(),
+                                span,
+                            ));
+                        }
+                        None => return Err(Error::UnexpectedClose(trimmed.into_owned(), span)),
+                        Some(_) => {}
+                    }
+                    self.tokens.push_back(Tokens::CloseNode(Name::new(trimmed)));
+                    return Ok(());
                 }
                 if trimmed.is_empty() {
-                    continue;
+                    return Ok(());
                 }
-                tokens.push(Tokens::Text(trimmed.into()));
+                self.tokens
+                    .push_back(Tokens::Text(decode(trimmed, self.entities)));
             }
         }
+        Ok(())
+    }
+
+    // HTML names aren't case sensitive, so are all read as lowercase
+    fn name(&self, name: Cow<'a, str>) -> Cow<'a, str> {
+        if self.html && name.contains(|c: char| c.is_uppercase()) {
+            Cow::Owned(name.to_lowercase())
+        } else {
+            name
+        }
+    }
+
+    // Closes the element at `depth` in node_names and everything opened inside of it
+    fn close_to(&mut self, depth: usize) {
+        for (name, _) in self.node_names.drain(depth..).rev() {
+            self.tokens.push_back(Tokens::CloseNode(Name::new(name)));
+        }
+    }
+
+    // Closes the elements from `depth` in node_names on as `close_to` does,
+    // warning that each of them was never closed
+    fn close_unclosed(&mut self, depth: usize) {
+        for (name, span) in self.node_names[depth..].iter().rev() {
+            self.warnings
+                .push(Error::UnclosedTag(name.to_string(), span.clone()));
+        }
+        self.close_to(depth);
+    }
+
+    // Stops on `e`, or when lenient keeps it as a warning and carries on
+    fn recover(&mut self, e: Error) -> Result<(), Error> {
+        if !self.lenient {
+            return Err(e);
+        }
+        self.warnings.push(e);
+        Ok(())
     }
-    Ok(tokens)
-}
\ No newline at end of file
+
+    // Closes the open elements that end where `opening` starts, such as a `<li>`
+    // at the start of the next one. Only elements that may leave out their end
+    // tag are looked through.
+    fn close_implied(&mut self, opening: &str) {
+        let mut depth = None;
+        for (i, (open, _)) in self.node_names.iter().enumerate().rev() {
+            if html::closes(open, opening) {
+                depth = Some(i);
+            }
+            if !html::has_optional_end(open) {
+                break;
+            }
+        }
+        if let Some(depth) = depth {
+            self.close_to(depth);
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        if self.html {
+            self.close_to(0);
+            return Ok(());
+        }
+        if self.lenient {
+            self.close_unclosed(0);
+            return Ok(());
+        }
+        if let Some((name, span)) = self.node_names.pop() {
+            return Err(Error::UnclosedTag(name.into_owned(), span));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::Tokens;
+
+pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+pub const XMLNS_NAMESPACE: &str = "http://www.w3.org/2000/xmlns/";
+
+// The name of an element or attribute as written in the document, along with
+// the namespace URI its prefix (or the default namespace) was bound to. The
+// URI is shared by every name resolved to it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Name<'a> {
+    pub qualified: Cow<'a, str>,
+    pub namespace: Option<Arc<str>>,
+}
+
+impl<'a> Name<'a> {
+    pub fn new(qualified: impl Into<Cow<'a, str>>) -> Name<'a> {
+        Name {
+            qualified: qualified.into(),
+            namespace: None,
+        }
+    }
+
+    pub fn into_owned(self) -> Name<'static> {
+        Name {
+            qualified: Cow::Owned(self.qualified.into_owned()),
+            namespace: self.namespace,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.qualified
+    }
+
+    pub fn prefix(&self) -> Option<&str> {
+        self.qualified.split_once(':').map(|(prefix, _)| prefix)
+    }
+
+    pub fn local_name(&self) -> &str {
+        self.qualified
+            .split_once(':')
+            .map_or(&self.qualified, |(_, local)| local)
+    }
+
+    // Whether this is `local_name` in `namespace`, whatever prefix it was written with
+    pub fn is(&self, namespace: &str, local_name: &str) -> bool {
+        self.namespace.as_deref() == Some(namespace) && self.local_name() == local_name
+    }
+}
+
+impl<'a> From<&'a str> for Name<'a> {
+    fn from(qualified: &'a str) -> Self {
+        Name::new(qualified)
+    }
+}
+
+impl From<String> for Name<'_> {
+    fn from(qualified: String) -> Self {
+        Name::new(qualified)
+    }
+}
+
+impl From<Name<'_>> for String {
+    fn from(name: Name) -> Self {
+        name.qualified.into_owned()
+    }
+}
+
+impl PartialEq<str> for Name<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.qualified == other
+    }
+}
+
+impl PartialEq<&str> for Name<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.qualified == *other
+    }
+}
+
+impl fmt::Display for Name<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.qualified)
+    }
+}
+
+// An open element and the prefixes it declared, `None` being the default namespace
+pub(crate) type Scope<'a> = (Cow<'a, str>, Vec<(Option<String>, Arc<str>)>);
+
+// Tracks the `xmlns` and `xmlns:*` parameters in scope as tokens go past.
+// Prefixes that were never declared are left without a namespace.
+#[derive(Default)]
+pub(crate) struct Namespaces<'a> {
+    scopes: Vec<Scope<'a>>,
+}
+
+impl<'a> Namespaces<'a> {
+    // Takes an OpenNode followed by all of its parameters, bringing any
+    // declarations into scope before resolving the node and parameter names
+    pub(crate) fn open(&mut self, tokens: &mut [Tokens<'a>]) {
+        let Some(Tokens::OpenNode(name)) = tokens.first() else {
+            return;
+        };
+        let qualified = name.qualified.clone();
+        let mut declarations = vec![];
+        for pair in tokens[1..].windows(2) {
+            if let [Tokens::ParameterName(parameter), Tokens::ParameterValue(uri)] = pair {
+                if parameter == "xmlns" {
+                    declarations.push((None, Arc::from(&**uri)));
+                } else if let Some(prefix) = parameter.qualified.strip_prefix("xmlns:") {
+                    declarations.push((Some(prefix.to_owned()), Arc::from(&**uri)));
+                }
+            }
+        }
+        self.scopes.push((qualified, declarations));
+        for token in tokens {
+            match token {
+                Tokens::OpenNode(name) => {
+                    name.namespace = lookup(&self.scopes, name.prefix());
+                }
+                Tokens::ParameterName(name) => {
+                    name.namespace = if name == "xmlns" {
+                        Some(XMLNS_NAMESPACE.into())
+                    } else {
+                        // Unprefixed attributes are never in the default namespace
+                        name.prefix()
+                            .and_then(|prefix| lookup(&self.scopes, Some(prefix)))
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Resolves a CloseNode and takes the declarations of the node it closes
+    // out of scope
+    pub(crate) fn close(&mut self, name: &mut Name<'a>) {
+        let scopes = &self.scopes;
+        let depth = scopes.iter().rposition(|(open, _)| *open == name.qualified);
+        let in_scope = depth.map_or(scopes.as_slice(), |depth| &scopes[..=depth]);
+        name.namespace = lookup(in_scope, name.prefix());
+        if let Some(depth) = depth {
+            self.scopes.truncate(depth);
+        }
+    }
+}
+
+pub(crate) fn lookup(scopes: &[Scope], prefix: Option<&str>) -> Option<Arc<str>> {
+    match prefix {
+        Some("xml") => return Some(XML_NAMESPACE.into()),
+        Some("xmlns") => return Some(XMLNS_NAMESPACE.into()),
+        _ => {}
+    }
+    scopes
+        .iter()
+        .rev()
+        .flat_map(|(_, declarations)| declarations.iter().rev())
+        .find(|(declared, _)| declared.as_deref() == prefix)
+        // `xmlns=""` removes the default namespace
+        .filter(|(_, uri)| !uri.is_empty())
+        .map(|(_, uri)| uri.clone())
+}
@@ -0,0 +1,181 @@
+use std::io::{BufRead, ErrorKind};
+
+use crate::{Entities, Error, Lexer, Span, StrChars, Tokenizer, Tokens};
+
+// A pull parser over any buffered reader. Tokens are produced as they are
+// asked for, so only the current node, its text and the stack of open nodes
+// are held in memory rather than the whole document. Nothing can be borrowed
+// from the reader, so every token owns its text.
+pub struct Reader<R: BufRead> {
+    tokens: Tokenizer<'static, Lexer<'static, Utf8Chars<R>>>,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_entities(reader, Entities::Xml)
+    }
+
+    pub fn with_entities(reader: R, entities: Entities) -> Self {
+        Reader {
+            tokens: Tokenizer::new(Lexer::new(Utf8Chars::new(reader)), entities),
+        }
+    }
+
+    // Reads HTML rather than XML, as `tokenize_html` does
+    pub fn html(reader: R) -> Self {
+        Reader {
+            tokens: Tokenizer::html(Lexer::new(Utf8Chars::new(reader))),
+        }
+    }
+
+    // Recovers from malformed XML instead of stopping at it, only failing if
+    // the reader does. Whatever was recovered from is kept in `warnings`.
+    pub fn lenient(reader: R) -> Self {
+        Reader {
+            tokens: Tokenizer::lenient(Lexer::new(Utf8Chars::new(reader)).lenient(), Entities::Xml),
+        }
+    }
+
+    // The mistakes in the document read so far, always empty unless lenient
+    pub fn warnings(&self) -> &[Error] {
+        self.tokens.warnings()
+    }
+
+    // Where the start tag of the last OpenNode read is, for pointing at the
+    // element in anything found wrong with it
+    pub fn span(&self) -> Option<&Span> {
+        self.tokens.span()
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Tokens<'static>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tokens.next()
+    }
+}
+
+// A pull parser over a document already in memory. Text is sliced out of the
+// string rather than copied, so a token only owns its text when decoding an
+// entity changed it.
+pub struct StrReader<'a> {
+    tokens: Tokenizer<'a, Lexer<'a, StrChars<'a>>>,
+}
+
+impl<'a> StrReader<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self::with_entities(input, Entities::Xml)
+    }
+
+    pub fn with_entities(input: &'a str, entities: Entities) -> Self {
+        StrReader {
+            tokens: Tokenizer::new(Lexer::borrowing(input), entities),
+        }
+    }
+
+    // Reads HTML rather than XML, as `tokenize_html` does
+    pub fn html(input: &'a str) -> Self {
+        StrReader {
+            tokens: Tokenizer::html(Lexer::borrowing(input)),
+        }
+    }
+
+    // Recovers from malformed XML as `Reader::lenient` does
+    pub fn lenient(input: &'a str) -> Self {
+        StrReader {
+            tokens: Tokenizer::lenient(Lexer::borrowing(input).lenient(), Entities::Xml),
+        }
+    }
+
+    // The mistakes in the document read so far, always empty unless lenient
+    pub fn warnings(&self) -> &[Error] {
+        self.tokens.warnings()
+    }
+
+    // Where the start tag of the last OpenNode read is, for pointing at the
+    // element in anything found wrong with it
+    pub fn span(&self) -> Option<&Span> {
+        self.tokens.span()
+    }
+}
+
+impl<'a> Iterator for StrReader<'a> {
+    type Item = Result<Tokens<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tokens.next()
+    }
+}
+
+// Decodes UTF-8 a character at a time straight out of the reader's buffer
+struct Utf8Chars<R: BufRead> {
+    reader: R,
+    // Only used to say where a read went wrong
+    span: Span,
+}
+
+impl<R: BufRead> Utf8Chars<R> {
+    fn new(reader: R) -> Self {
+        Utf8Chars {
+            reader,
+            span: Span::new(),
+        }
+    }
+
+    fn read_byte(&mut self) -> Result<Option<u8>, Error> {
+        loop {
+            match self.reader.fill_buf() {
+                Ok([]) => return Ok(None),
+                Ok(buffer) => {
+                    let byte = buffer[0];
+                    self.reader.consume(1);
+                    return Ok(Some(byte));
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(Error::Io(e.kind(), self.span.clone())),
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Utf8Chars<R> {
+    type Item = Result<char, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = match self.read_byte() {
+            Ok(Some(byte)) => byte,
+            Ok(None) => return None,
+            Err(e) => return Some(Err(e)),
+        };
+        let width = match first {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 0,
+        };
+        let mut bytes = [first, 0, 0, 0];
+        for byte in bytes.iter_mut().take(width).skip(1) {
+            match self.read_byte() {
+                Ok(Some(next)) => *byte = next,
+                Ok(None) => break,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        let Some(c) = bytes
+            .get(..width)
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .and_then(|s| s.chars().next())
+        else {
+            return Some(Err(Error::Io(ErrorKind::InvalidData, self.span.clone())));
+        };
+        if c == '\n' {
+            self.span.row += 1;
+            self.span.column = 1;
+        } else {
+            self.span.column += 1;
+        }
+        Some(Ok(c))
+    }
+}
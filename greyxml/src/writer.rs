@@ -0,0 +1,308 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use crate::namespace::{lookup, Scope};
+use crate::{Ast, Document, Name, Node, Tokens, XMLNS_NAMESPACE, XML_NAMESPACE};
+
+// Writes Tokens, or a whole Document, back out as XML. Text and parameter
+// values are escaped, and any namespace a name was resolved to that is not
+// declared where it is written gets an `xmlns` added for it.
+pub struct Writer<W: Write> {
+    out: W,
+    // How many spaces each level is indented by, None writes everything on one line
+    indent: Option<usize>,
+    // A node that has been opened but whose parameters may still be coming
+    pending: Option<Pending>,
+    scopes: Vec<Scope<'static>>,
+    // Text is kept on the same line as the tags around it
+    after_text: bool,
+    written: bool,
+}
+
+struct Pending {
+    name: Name<'static>,
+    parameters: Vec<(Name<'static>, String)>,
+    parameter_name: Option<Name<'static>>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out: W) -> Self {
+        Writer {
+            out,
+            indent: None,
+            pending: None,
+            scopes: vec![],
+            after_text: false,
+            written: false,
+        }
+    }
+
+    // Puts every node on its own line, indented `indent` spaces per level
+    pub fn pretty(out: W, indent: usize) -> Self {
+        Writer {
+            indent: Some(indent),
+            ..Writer::new(out)
+        }
+    }
+
+    pub fn write_token(&mut self, token: &Tokens) -> io::Result<()> {
+        match token {
+            Tokens::ParameterName(name) => {
+                if let Some(pending) = &mut self.pending {
+                    pending.parameter_name = Some(name.clone().into_owned());
+                }
+                return Ok(());
+            }
+            Tokens::ParameterValue(value) => {
+                if let Some(pending) = &mut self.pending {
+                    if let Some(name) = pending.parameter_name.take() {
+                        pending.parameters.push((name, value.to_string()));
+                    }
+                }
+                return Ok(());
+            }
+            Tokens::CloseNode(name) => {
+                if let Some(pending) = self.pending.take() {
+                    if pending.name.qualified == name.qualified {
+                        self.write_start(pending, true)?;
+                        self.scopes.pop();
+                        return Ok(());
+                    }
+                    self.write_start(pending, false)?;
+                }
+            }
+            _ => self.flush_pending()?,
+        }
+        match token {
+            Tokens::OpenNode(name) => {
+                self.pending = Some(Pending::new(name.clone().into_owned()));
+            }
+            Tokens::CloseNode(name) => {
+                self.scopes.pop();
+                if !self.after_text {
+                    self.new_line()?;
+                }
+                write!(self.out, "</{}>", name.qualified)?;
+                self.after_text = false;
+            }
+            Tokens::Text(text) => {
+                self.out.write_all(escape(text, false).as_bytes())?;
+                self.after_text = true;
+                self.written = true;
+            }
+            Tokens::CData(text) => self.write_cdata(text)?,
+            Tokens::Comment(comment) => self.write_markup(format_args!("<!--{comment}-->"))?,
+            Tokens::ProcessingInstruction(target, content) if content.is_empty() => {
+                self.write_markup(format_args!("<?{target}?>"))?
+            }
+            Tokens::ProcessingInstruction(target, content) => {
+                self.write_markup(format_args!("<?{target} {content}?>"))?
+            }
+            Tokens::Doctype(doctype) => self.write_markup(format_args!("<!DOCTYPE {doctype}>"))?,
+            Tokens::ParameterName(_) | Tokens::ParameterValue(_) => {}
+        }
+        Ok(())
+    }
+
+    // Wraps `text` in a CDATA section, so markup inside it is left as it is.
+    // A `]]>` in the text is split across two sections.
+    pub fn write_cdata(&mut self, text: &str) -> io::Result<()> {
+        self.flush_pending()?;
+        let text = text.replace("]]>", "]]]]><![CDATA[>");
+        write!(self.out, "<![CDATA[{text}]]>")?;
+        self.after_text = true;
+        self.written = true;
+        Ok(())
+    }
+
+    pub fn write_tokens<'a, 'b: 'a, I>(&mut self, tokens: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a Tokens<'b>>,
+    {
+        tokens
+            .into_iter()
+            .try_for_each(|token| self.write_token(token))
+    }
+
+    pub fn write_document(&mut self, document: &Document) -> io::Result<()> {
+        document
+            .children()
+            .try_for_each(|node| self.write_node(node))
+    }
+
+    // Writes a node and everything inside of it
+    pub fn write_node(&mut self, node: Node) -> io::Result<()> {
+        let token = match node.ast() {
+            Ast::ElementNode(name, attributes) => {
+                self.write_token(&Tokens::OpenNode(name.clone()))?;
+                for attribute in attributes {
+                    self.write_token(&Tokens::ParameterName(attribute.name.clone()))?;
+                    self.write_token(&Tokens::ParameterValue((&attribute.value).into()))?;
+                }
+                node.children()
+                    .try_for_each(|child| self.write_node(child))?;
+                Tokens::CloseNode(name.clone())
+            }
+            Ast::DescriptorNode(target, content) => {
+                Tokens::ProcessingInstruction(target.into(), content.into())
+            }
+            Ast::DoctypeNode(doctype) => Tokens::Doctype(doctype.into()),
+            Ast::TextNode(text) => Tokens::Text(text.into()),
+            Ast::CDataNode(text) => Tokens::CData(text.into()),
+            Ast::CommentNode(comment) => Tokens::Comment(comment.into()),
+        };
+        self.write_token(&token)
+    }
+
+    // Finishes off anything still waiting to be written and hands back the output
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush_pending()?;
+        if self.indent.is_some() && self.written {
+            self.out.write_all(b"\n")?;
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn flush_pending(&mut self) -> io::Result<()> {
+        match self.pending.take() {
+            Some(pending) => self.write_start(pending, false),
+            None => Ok(()),
+        }
+    }
+
+    fn write_start(&mut self, pending: Pending, empty: bool) -> io::Result<()> {
+        if !self.after_text {
+            self.new_line()?;
+        }
+        self.after_text = false;
+        self.written = true;
+        let Pending {
+            name,
+            mut parameters,
+            ..
+        } = pending;
+        self.scopes
+            .push((name.qualified.clone(), declarations(&parameters)));
+        let mut undeclared = vec![];
+        if let Some(declaration) = self.undeclared(&name, true) {
+            undeclared.push(declaration);
+        }
+        for (parameter, _) in &parameters {
+            if let Some(declaration) = self.undeclared(parameter, false) {
+                if !undeclared.contains(&declaration) {
+                    undeclared.push(declaration);
+                }
+            }
+        }
+        if let Some((_, declared)) = self.scopes.last_mut() {
+            declared.extend(undeclared.iter().cloned());
+        }
+        for (prefix, uri) in undeclared {
+            let qualified = match prefix {
+                Some(prefix) => format!("xmlns:{prefix}"),
+                None => "xmlns".into(),
+            };
+            parameters.push((Name::new(qualified), uri.to_string()));
+        }
+        write!(self.out, "<{name}")?;
+        self.write_parameters(&parameters)?;
+        self.out.write_all(if empty { b" />" } else { b">" })
+    }
+
+    // Comments, processing instructions and doctypes go on their own line like nodes
+    fn write_markup(&mut self, markup: fmt::Arguments) -> io::Result<()> {
+        if !self.after_text {
+            self.new_line()?;
+        }
+        self.out.write_fmt(markup)?;
+        self.after_text = false;
+        self.written = true;
+        Ok(())
+    }
+
+    fn write_parameters(&mut self, parameters: &[(Name, String)]) -> io::Result<()> {
+        for (name, value) in parameters {
+            write!(self.out, " {name}=\"{}\"", escape(value, true))?;
+        }
+        Ok(())
+    }
+
+    // The declaration needed for `name` to resolve to the namespace it has
+    fn undeclared(&self, name: &Name, is_node: bool) -> Option<(Option<String>, Arc<str>)> {
+        let prefix = name.prefix();
+        if matches!(prefix, Some("xml" | "xmlns")) || name == "xmlns" {
+            return None;
+        }
+        // Unprefixed parameters are never in a namespace
+        if prefix.is_none() && !is_node {
+            return None;
+        }
+        let namespace = name.namespace.as_deref();
+        if matches!(namespace, Some(XML_NAMESPACE | XMLNS_NAMESPACE)) {
+            return None;
+        }
+        if lookup(&self.scopes, prefix).as_deref() == namespace {
+            return None;
+        }
+        // Nothing can be declared to take a prefix out of a namespace
+        if prefix.is_some() && namespace.is_none() {
+            return None;
+        }
+        Some((
+            prefix.map(str::to_owned),
+            Arc::from(namespace.unwrap_or_default()),
+        ))
+    }
+
+    fn new_line(&mut self) -> io::Result<()> {
+        let Some(indent) = self.indent else {
+            return Ok(());
+        };
+        if self.written {
+            self.out.write_all(b"\n")?;
+        }
+        // Every node still open is one level of indentation
+        let depth = self.scopes.len();
+        write!(self.out, "{:1$}", "", indent * depth)
+    }
+}
+
+impl Pending {
+    fn new(name: Name<'static>) -> Self {
+        Pending {
+            name,
+            parameters: vec![],
+            parameter_name: None,
+        }
+    }
+}
+
+fn declarations(parameters: &[(Name, String)]) -> Vec<(Option<String>, Arc<str>)> {
+    parameters
+        .iter()
+        .filter_map(|(name, uri)| {
+            if name == "xmlns" {
+                Some((None, Arc::from(uri.as_str())))
+            } else {
+                let prefix = name.qualified.strip_prefix("xmlns:")?;
+                Some((Some(prefix.to_owned()), Arc::from(uri.as_str())))
+            }
+        })
+        .collect()
+}
+
+fn escape(text: &str, in_parameter: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if in_parameter => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
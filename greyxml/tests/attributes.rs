@@ -0,0 +1,160 @@
+use std::io::Cursor;
+
+use greyxml::{lex, parse_document, tokenize, tokenize_html, Error, Lexicals, Reader, Tokens};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn tokenize_str(xml: &str) -> Result<Vec<Tokens<'_>>, Error> {
+    let lexed: Vec<Lexicals> = lex(xml)?;
+    tokenize(&mut lexed.into_iter())
+}
+
+fn tokenize_html_str(html: &str) -> Result<Vec<Tokens<'_>>, Error> {
+    let lexed: Vec<Lexicals> = lex(html)?;
+    tokenize_html(&mut lexed.into_iter())
+}
+
+#[test]
+fn single_quotes() -> TestResult<()> {
+    let tokens = tokenize_str(r#"<a title='Say "hi"' alt="it's" empty=''>x</a>"#)?;
+    assert_eq!(
+        tokens,
+        vec![
+            Tokens::OpenNode("a".into()),
+            Tokens::ParameterName("title".into()),
+            Tokens::ParameterValue("Say \"hi\"".into()),
+            Tokens::ParameterName("alt".into()),
+            Tokens::ParameterValue("it's".into()),
+            Tokens::ParameterName("empty".into()),
+            Tokens::ParameterValue("".into()),
+            Tokens::Text("x".into()),
+            Tokens::CloseNode("a".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn self_closing() -> TestResult<()> {
+    let tokens = tokenize_str("<a><b/><c /><link href='x/y'/></a>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            Tokens::OpenNode("a".into()),
+            Tokens::OpenNode("b".into()),
+            Tokens::CloseNode("b".into()),
+            Tokens::OpenNode("c".into()),
+            Tokens::CloseNode("c".into()),
+            Tokens::OpenNode("link".into()),
+            Tokens::ParameterName("href".into()),
+            Tokens::ParameterValue("x/y".into()),
+            Tokens::CloseNode("link".into()),
+            Tokens::CloseNode("a".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn attributes_across_lines() -> TestResult<()> {
+    let tokens = tokenize_str("<a\n  b=\"1\"\n\tc='2'\n/>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            Tokens::OpenNode("a".into()),
+            Tokens::ParameterName("b".into()),
+            Tokens::ParameterValue("1".into()),
+            Tokens::ParameterName("c".into()),
+            Tokens::ParameterValue("2".into()),
+            Tokens::CloseNode("a".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn unquoted_values() -> TestResult<()> {
+    let html = "<p><img src=foo.png alt=&quot;Foo&quot;/></p>";
+    assert!(matches!(
+        tokenize_str(html),
+        Err(Error::UnquotedAttribute(_))
+    ));
+    let tokens = tokenize_html_str(html)?;
+    assert_eq!(
+        tokens,
+        vec![
+            Tokens::OpenNode("p".into()),
+            Tokens::OpenNode("img".into()),
+            Tokens::ParameterName("src".into()),
+            Tokens::ParameterValue("foo.png".into()),
+            Tokens::ParameterName("alt".into()),
+            Tokens::ParameterValue("\"Foo\"".into()),
+            Tokens::CloseNode("img".into()),
+            Tokens::CloseNode("p".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn boolean_attributes() -> TestResult<()> {
+    let tokens = tokenize_html_str("<input disabled type=checkbox checked></input>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            Tokens::OpenNode("input".into()),
+            Tokens::ParameterName("disabled".into()),
+            Tokens::ParameterValue("".into()),
+            Tokens::ParameterName("type".into()),
+            Tokens::ParameterValue("checkbox".into()),
+            Tokens::ParameterName("checked".into()),
+            Tokens::ParameterValue("".into()),
+            Tokens::CloseNode("input".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn html_reader() -> TestResult<()> {
+    let tokens =
+        Reader::html(Cursor::new("<a href=/x>&nbsp;</a>")).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        tokens,
+        vec![
+            Tokens::OpenNode("a".into()),
+            Tokens::ParameterName("href".into()),
+            Tokens::ParameterValue("/x".into()),
+            Tokens::Text("\u{a0}".into()),
+            Tokens::CloseNode("a".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn atom_links() -> TestResult<()> {
+    let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <link rel="alternate" href='https://example.com/a?b=1&amp;c=2'/>
+    <link rel='enclosure' type="audio/mpeg" href="https://example.com/a.mp3" />
+  </entry>
+</feed>"#;
+    let document = parse_document(atom.as_bytes())?;
+    let entry = document
+        .root()
+        .and_then(|feed| feed.child("entry"))
+        .unwrap();
+    let links: Vec<_> = entry
+        .elements()
+        .map(|link| (link.attribute("rel"), link.attribute("href")))
+        .collect();
+    assert_eq!(
+        links,
+        vec![
+            (Some("alternate"), Some("https://example.com/a?b=1&c=2")),
+            (Some("enclosure"), Some("https://example.com/a.mp3")),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use std::fs;
+
+use greyxml::{lex, tokenize, Error, Lexicals, Reader, StrReader, Tokens};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn is_borrowed(text: &str, source: &str) -> bool {
+    source.as_bytes().as_ptr_range().contains(&text.as_ptr())
+}
+
+#[test]
+fn text_is_sliced_from_the_input() -> TestResult<()> {
+    let xml = r#"<item id='1'><title>Tom &amp; Jerry</title><link> https://example.com/ </link><![CDATA[<b>x</b>]]></item>"#;
+    let tokens = tokenize(&mut lex(xml)?.into_iter())?;
+    for token in &tokens {
+        match token {
+            Tokens::OpenNode(name) | Tokens::CloseNode(name) | Tokens::ParameterName(name) => {
+                assert!(is_borrowed(&name.qualified, xml), "{token:?}");
+            }
+            // Decoding the entity is the only thing that needs a copy
+            Tokens::Text(text) if text == "Tom & Jerry" => assert!(!is_borrowed(text, xml)),
+            Tokens::Text(text) | Tokens::ParameterValue(text) | Tokens::CData(text) => {
+                assert!(is_borrowed(text, xml), "{token:?}");
+            }
+            _ => {}
+        }
+    }
+    assert!(tokens.contains(&Tokens::Text("https://example.com/".into())));
+    Ok(())
+}
+
+#[test]
+fn markup_is_sliced_from_the_input() -> TestResult<()> {
+    let xml = "<!DOCTYPE html><?xml-stylesheet href=\"a.xsl\"?><!-- hi --><a/>";
+    let lexed = lex(xml)?;
+    for lexical in &lexed {
+        match lexical {
+            Lexicals::Doctype(text, _)
+            | Lexicals::ProcessingInstruction(text, _)
+            | Lexicals::Comment(text, _)
+            | Lexicals::Text(text, _) => assert!(is_borrowed(text, xml), "{lexical:?}"),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn str_reader_matches_reader() -> TestResult<()> {
+    for path in [
+        "./tests/test.rss",
+        "./tests/@sarahgreywolf.rss",
+        "./tests/Kisserss.rss",
+    ] {
+        let data = fs::read_to_string(path)?;
+        let borrowed = StrReader::new(&data).collect::<Result<Vec<_>, Error>>()?;
+        let owned = Reader::new(data.as_bytes()).collect::<Result<Vec<_>, Error>>()?;
+        assert_eq!(borrowed, owned, "{path}");
+    }
+    Ok(())
+}
+
+#[test]
+fn tokens_can_outlive_their_input() -> TestResult<()> {
+    let owned: Vec<Tokens<'static>> = {
+        let xml = String::from("<a href=\"x\">1 > 0</a>");
+        StrReader::new(&xml)
+            .map(|token| token.map(Tokens::into_owned))
+            .collect::<Result<_, Error>>()?
+    };
+    assert_eq!(
+        owned,
+        vec![
+            Tokens::OpenNode("a".into()),
+            Tokens::ParameterName("href".into()),
+            Tokens::ParameterValue("x".into()),
+            Tokens::Text("1 > 0".into()),
+            Tokens::CloseNode("a".into()),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use greyxml::{parse_document, Ast};
+use std::fs::File;
+use std::io::BufReader;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const CONTENT: &str = "http://purl.org/rss/1.0/modules/content/";
+
+#[test]
+fn navigate_feed() -> TestResult<()> {
+    let document = parse_document(BufReader::new(File::open("./tests/test.rss")?))?;
+    let descriptor = document.children().next().ok_or("Empty document")?;
+    assert_eq!(
+        descriptor.ast(),
+        &Ast::DescriptorNode("xml".into(), r#"version="1.0" encoding="UTF-8""#.into())
+    );
+
+    let rss = document.root().ok_or("No root element")?;
+    assert_eq!(rss.name().map(|name| name.as_str()), Some("rss"));
+    assert_eq!(rss.attribute("version"), Some("2.0"));
+    assert!(rss.parent().is_none());
+
+    let channel = rss.child("channel").ok_or("No channel")?;
+    let item = channel.child("item").ok_or("No item")?;
+    assert_eq!(item.parent().map(|parent| parent.id()), Some(channel.id()));
+    assert!(item.child_ns(CONTENT, "encoded").is_some());
+    assert!(channel.child("missing").is_none());
+    Ok(())
+}
+
+#[test]
+fn attributes_and_text() -> TestResult<()> {
+    let xml = r#"<a href="https://example.com" rel="me">Hello:<b>there</b>, world</a>"#;
+    let document = parse_document(xml.as_bytes())?;
+    let a = document.root().ok_or("No root element")?;
+    assert_eq!(a.attribute("href"), Some("https://example.com"));
+    assert_eq!(a.attribute("rel"), Some("me"));
+    assert_eq!(a.attribute("title"), None);
+    assert_eq!(a.children().count(), 3);
+    assert_eq!(a.elements().count(), 1);
+    assert_eq!(a.text(), "Hello:there, world");
+    assert_eq!(a.child("b").map(|b| b.text()).as_deref(), Some("there"));
+    Ok(())
+}
+
+#[test]
+fn errors_are_passed_on() {
+    assert!(parse_document("<a><b></a>".as_bytes()).is_err());
+}
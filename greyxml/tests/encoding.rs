@@ -0,0 +1,99 @@
+use greyxml::{DecodingReader, Error, Reader, Tokens};
+use std::io::Read;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn decode(bytes: &[u8], charset: Option<&str>) -> TestResult<(&'static str, String)> {
+    let mut reader = DecodingReader::new(bytes, charset)?;
+    let mut decoded = String::new();
+    reader.read_to_string(&mut decoded)?;
+    Ok((reader.encoding(), decoded))
+}
+
+fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+    text.encode_utf16()
+        .flat_map(|unit| {
+            if big_endian {
+                unit.to_be_bytes()
+            } else {
+                unit.to_le_bytes()
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn utf8_by_default() -> TestResult<()> {
+    let (encoding, decoded) = decode("<a>Caf\u{e9}</a>".as_bytes(), None)?;
+    assert_eq!(encoding, "UTF-8");
+    assert_eq!(decoded, "<a>Caf\u{e9}</a>");
+    Ok(())
+}
+
+#[test]
+fn byte_order_marks() -> TestResult<()> {
+    let xml = "<?xml version=\"1.0\"?><a>\u{2603}</a>";
+    let mut utf8 = b"\xef\xbb\xbf".to_vec();
+    utf8.extend_from_slice(xml.as_bytes());
+    assert_eq!(decode(&utf8, None)?, ("UTF-8", xml.into()));
+
+    let mut le = vec![0xff, 0xfe];
+    le.extend(utf16(xml, false));
+    assert_eq!(decode(&le, None)?, ("UTF-16LE", xml.into()));
+
+    let mut be = vec![0xfe, 0xff];
+    be.extend(utf16(xml, true));
+    assert_eq!(decode(&be, None)?, ("UTF-16BE", xml.into()));
+    Ok(())
+}
+
+#[test]
+fn utf16_without_byte_order_mark() -> TestResult<()> {
+    let xml = "<rss>\u{1f980}</rss>";
+    assert_eq!(decode(&utf16(xml, false), None)?, ("UTF-16LE", xml.into()));
+    assert_eq!(decode(&utf16(xml, true), None)?, ("UTF-16BE", xml.into()));
+    Ok(())
+}
+
+#[test]
+fn declared_encoding() -> TestResult<()> {
+    let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><a>Caf\xe9 \x93quoted\x94</a>";
+    let (encoding, decoded) = decode(latin1, None)?;
+    assert_eq!(encoding, "windows-1252");
+    assert!(decoded.ends_with("<a>Caf\u{e9} \u{201c}quoted\u{201d}</a>"));
+
+    let shift_jis = b"<?xml version='1.0' encoding = 'Shift_JIS' ?><a>\x93\xfa\x96\x7b</a>";
+    let (encoding, decoded) = decode(shift_jis, None)?;
+    assert_eq!(encoding, "Shift_JIS");
+    assert!(decoded.ends_with("<a>\u{65e5}\u{672c}</a>"));
+    Ok(())
+}
+
+#[test]
+fn charset_is_the_last_resort() -> TestResult<()> {
+    let body = b"<a>Caf\xe9</a>";
+    assert_eq!(decode(body, Some("windows-1252"))?.1, "<a>Caf\u{e9}</a>");
+    // Invalid UTF-8 is replaced rather than failing
+    assert_eq!(decode(body, None)?.1, "<a>Caf\u{fffd}</a>");
+
+    let declared = b"<?xml version=\"1.0\" encoding=\"windows-1252\"?><a>\xe9</a>";
+    assert_eq!(decode(declared, Some("utf-8"))?.0, "windows-1252");
+
+    let mut bom = b"\xef\xbb\xbf".to_vec();
+    bom.extend_from_slice(declared);
+    assert_eq!(decode(&bom, Some("koi8-r"))?.0, "UTF-8");
+    Ok(())
+}
+
+#[test]
+fn read_through_reader() -> TestResult<()> {
+    let mut xml = vec![0xff, 0xfe];
+    xml.extend(utf16(
+        "<?xml version=\"1.0\" encoding=\"UTF-16\"?><title>\u{65e5}\u{672c}</title>",
+        false,
+    ));
+    let tokens = Reader::new(DecodingReader::new(xml.as_slice(), None)?)
+        .collect::<Result<Vec<Tokens>, Error>>()?;
+    assert_eq!(tokens[2], Tokens::Text("\u{65e5}\u{672c}".into()));
+    Ok(())
+}
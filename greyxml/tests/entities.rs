@@ -0,0 +1,73 @@
+use greyxml::{decode_entities, lex, tokenize, tokenize_with_entities, Entities, Tokens};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn predefined_entities() {
+    assert_eq!(
+        decode_entities(
+            "&lt;p&gt;Tom &amp; Jerry&apos;s &quot;show&quot;&lt;/p&gt;",
+            Entities::Xml
+        ),
+        "<p>Tom & Jerry's \"show\"</p>"
+    );
+}
+
+#[test]
+fn character_references() {
+    assert_eq!(
+        decode_entities("It&#8217;s &#x2014; &#X41;&#xA;", Entities::Xml),
+        "It\u{2019}s \u{2014} A\n"
+    );
+}
+
+#[test]
+fn unknown_and_stray_ampersands_are_kept() {
+    assert_eq!(
+        decode_entities("Q&A &nbsp; &#xZZ; &#0; &;", Entities::Xml),
+        "Q&A &nbsp; &#xZZ; &#0; &;"
+    );
+}
+
+#[test]
+fn amp_is_only_decoded_once() {
+    assert_eq!(decode_entities("&amp;lt;", Entities::Xml), "&lt;");
+}
+
+#[test]
+fn html_entities() {
+    assert_eq!(
+        decode_entities(
+            "a&nbsp;b &mdash; &hellip; &eacute;&Omega;&omega; &hearts;",
+            Entities::Html
+        ),
+        "a\u{a0}b \u{2014} \u{2026} \u{e9}\u{3a9}\u{3c9} \u{2665}"
+    );
+}
+
+#[test]
+fn tokens_are_decoded() -> TestResult<()> {
+    let xml = r#"<a title="Fish &amp; Chips">Caf&#233; &lt;3</a>"#;
+    let tokens = tokenize(&mut lex(xml)?.into_iter())?;
+    assert_eq!(
+        tokens,
+        vec![
+            Tokens::OpenNode("a".into()),
+            Tokens::ParameterName("title".into()),
+            Tokens::ParameterValue("Fish & Chips".into()),
+            Tokens::Text("Caf\u{e9} <3".into()),
+            Tokens::CloseNode("a".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn html_entities_are_opt_in() -> TestResult<()> {
+    let html = "<p>&copy; 2023</p>";
+    let xml_tokens = tokenize(&mut lex(html)?.into_iter())?;
+    assert_eq!(xml_tokens[1], Tokens::Text("&copy; 2023".into()));
+    let html_tokens = tokenize_with_entities(&mut lex(html)?.into_iter(), Entities::Html)?;
+    assert_eq!(html_tokens[1], Tokens::Text("\u{a9} 2023".into()));
+    Ok(())
+}
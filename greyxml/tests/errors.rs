@@ -0,0 +1,69 @@
+use greyxml::{lex, tokenize, Error, Lexicals, Tokens};
+
+fn tokenize_str(xml: &str) -> Result<Vec<Tokens<'_>>, Error> {
+    let lexed: Vec<Lexicals> = lex(xml)?;
+    tokenize(&mut lexed.into_iter())
+}
+
+#[test]
+fn unclosed_tag() {
+    let err = tokenize_str("<rss><channel></channel>").unwrap_err();
+    let Error::UnclosedTag(name, span) = err else {
+        panic!("Expected UnclosedTag, got {err:?}");
+    };
+    assert_eq!(name, "rss");
+    assert_eq!(span.row, 1);
+    assert_eq!(span.column, 2);
+}
+
+#[test]
+fn mismatched_close() {
+    let err = tokenize_str("<item>\n<title>Hello</item>").unwrap_err();
+    let Error::MismatchedClose(open, close, span) = err else {
+        panic!("Expected MismatchedClose, got {err:?}");
+    };
+    assert_eq!(open, "title");
+    assert_eq!(close, "item");
+    assert_eq!(span.row, 2);
+}
+
+#[test]
+fn unexpected_close() {
+    let err = tokenize_str("<a></a></b>").unwrap_err();
+    assert!(matches!(err, Error::UnexpectedClose(ref name, _) if name == "b"));
+}
+
+#[test]
+fn unterminated_attribute() {
+    let err = lex(r#"<link href="https://example.com>"#).unwrap_err();
+    let Error::UnterminatedAttribute(span) = err else {
+        panic!("Expected UnterminatedAttribute, got {err:?}");
+    };
+    assert_eq!(span.row, 1);
+}
+
+#[test]
+fn unexpected_eof() {
+    let err = lex("<rss><channel").unwrap_err();
+    assert!(matches!(err, Error::UnexpectedEof(_)));
+}
+
+#[test]
+fn stray_cdata_end() {
+    let err = lex("<title>Hello ]]> World</title>").unwrap_err();
+    assert!(matches!(err, Error::StrayCDataEnd(_)));
+}
+
+#[test]
+fn unbalanced_markup_in_cdata_is_allowed() {
+    let tokens = tokenize_str("<description><![CDATA[<p>Hello<br>World]]></description>");
+    assert!(tokens.is_ok(), "{tokens:?}");
+}
+
+#[test]
+fn self_closing_and_declarations_are_balanced() {
+    let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE rss>
+<rss version="2.0"><link href="x" /></rss>"#;
+    assert!(tokenize_str(xml).is_ok());
+}
@@ -0,0 +1,143 @@
+use greyxml::{lex, tokenize, tokenize_html, Error, Lexicals, Tokens};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn tokenize_html_str(html: &str) -> Result<Vec<Tokens<'_>>, Error> {
+    let lexed: Vec<Lexicals> = lex(html)?;
+    tokenize_html(&mut lexed.into_iter())
+}
+
+fn open(name: &str) -> Tokens<'_> {
+    Tokens::OpenNode(name.into())
+}
+
+fn close(name: &str) -> Tokens<'_> {
+    Tokens::CloseNode(name.into())
+}
+
+fn text(text: &str) -> Tokens<'_> {
+    Tokens::Text(text.into())
+}
+
+#[test]
+fn void_elements() -> TestResult<()> {
+    let tokens = tokenize_html_str("<p>a<br>b<img src=x.png/>c<input type=text></input></p>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("p"),
+            text("a"),
+            open("br"),
+            close("br"),
+            text("b"),
+            open("img"),
+            Tokens::ParameterName("src".into()),
+            Tokens::ParameterValue("x.png".into()),
+            close("img"),
+            text("c"),
+            open("input"),
+            Tokens::ParameterName("type".into()),
+            Tokens::ParameterValue("text".into()),
+            close("input"),
+            close("p"),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn implied_paragraph_ends() -> TestResult<()> {
+    let tokens = tokenize_html_str("<div><p>a<p>b<div>c</div><p>d</div>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("div"),
+            open("p"),
+            text("a"),
+            close("p"),
+            open("p"),
+            text("b"),
+            close("p"),
+            open("div"),
+            text("c"),
+            close("div"),
+            open("p"),
+            text("d"),
+            close("p"),
+            close("div"),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn implied_list_and_table_ends() -> TestResult<()> {
+    let tokens = tokenize_html_str(
+        "<ul><li>a<ul><li>b</ul><li>c</ul><table><tr><td>1<td>2<tr><th>3</table>",
+    )?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("ul"),
+            open("li"),
+            text("a"),
+            open("ul"),
+            open("li"),
+            text("b"),
+            close("li"),
+            close("ul"),
+            close("li"),
+            open("li"),
+            text("c"),
+            close("li"),
+            close("ul"),
+            open("table"),
+            open("tr"),
+            open("td"),
+            text("1"),
+            close("td"),
+            open("td"),
+            text("2"),
+            close("td"),
+            close("tr"),
+            open("tr"),
+            open("th"),
+            text("3"),
+            close("th"),
+            close("tr"),
+            close("table"),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn unbalanced_end_tags() -> TestResult<()> {
+    let tokens = tokenize_html_str("<B>bold <i>both</b> none</i></span><em>open")?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("b"),
+            text("bold"),
+            open("i"),
+            text("both"),
+            close("i"),
+            close("b"),
+            text("none"),
+            open("em"),
+            text("open"),
+            close("em"),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn xml_is_still_strict() -> TestResult<()> {
+    let lexed: Vec<Lexicals> = lex("<p>a<br>b</p>")?;
+    assert!(matches!(
+        tokenize(&mut lexed.into_iter()),
+        Err(Error::MismatchedClose(..))
+    ));
+    Ok(())
+}
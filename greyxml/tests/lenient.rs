@@ -0,0 +1,214 @@
+use greyxml::{lex, tokenize, Error, Reader, StrReader, Tokens};
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+// The tokens and warnings of reading `xml` leniently
+fn read_lenient(xml: &str) -> TestResult<(Vec<Tokens<'_>>, Vec<Error>)> {
+    let mut reader = StrReader::lenient(xml);
+    let tokens = reader.by_ref().collect::<Result<Vec<Tokens>, Error>>()?;
+    Ok((tokens, reader.warnings().to_vec()))
+}
+
+fn open(name: &str) -> Tokens<'_> {
+    Tokens::OpenNode(name.into())
+}
+
+fn close(name: &str) -> Tokens<'_> {
+    Tokens::CloseNode(name.into())
+}
+
+fn text(text: &str) -> Tokens<'_> {
+    Tokens::Text(text.into())
+}
+
+#[test]
+fn end_tag_closes_what_was_left_open() -> TestResult<()> {
+    let (tokens, warnings) = read_lenient("<item>\n<title>Hello</item>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("item"),
+            open("title"),
+            text("Hello"),
+            close("title"),
+            close("item"),
+        ]
+    );
+    let [Error::UnclosedTag(name, span)] = warnings.as_slice() else {
+        panic!("Expected one UnclosedTag, got {warnings:?}");
+    };
+    assert_eq!(name, "title");
+    assert_eq!(span.row, 2);
+    Ok(())
+}
+
+#[test]
+fn html_inside_title() -> TestResult<()> {
+    let (tokens, warnings) = read_lenient("<title>Tom <b>and</title><link/>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("title"),
+            text("Tom"),
+            open("b"),
+            text("and"),
+            close("b"),
+            close("title"),
+            open("link"),
+            close("link"),
+        ]
+    );
+    assert!(matches!(warnings.as_slice(), [Error::UnclosedTag(name, _)] if name == "b"));
+    Ok(())
+}
+
+#[test]
+fn stray_end_tag_is_dropped() -> TestResult<()> {
+    let (tokens, warnings) = read_lenient("<a></b></a>")?;
+    assert_eq!(tokens, vec![open("a"), close("a")]);
+    assert!(matches!(warnings.as_slice(), [Error::UnexpectedClose(name, _)] if name == "b"));
+    Ok(())
+}
+
+#[test]
+fn truncated_document_is_closed() -> TestResult<()> {
+    let (tokens, warnings) = read_lenient("<rss><channel><item><title>Cut sh")?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("rss"),
+            open("channel"),
+            open("item"),
+            open("title"),
+            text("Cut sh"),
+            close("title"),
+            close("item"),
+            close("channel"),
+            close("rss"),
+        ]
+    );
+    let unclosed: Vec<&str> = warnings
+        .iter()
+        .map(|warning| match warning {
+            Error::UnclosedTag(name, _) => name.as_str(),
+            _ => panic!("Expected UnclosedTag, got {warning:?}"),
+        })
+        .collect();
+    assert_eq!(unclosed, ["title", "item", "channel", "rss"]);
+    Ok(())
+}
+
+#[test]
+fn truncated_inside_a_tag() -> TestResult<()> {
+    let (tokens, warnings) = read_lenient("<channel><item><ti")?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("channel"),
+            open("item"),
+            close("item"),
+            close("channel")
+        ]
+    );
+    assert!(matches!(warnings[0], Error::UnexpectedEof(_)));
+    assert_eq!(warnings.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn stray_ampersand_is_literal() -> TestResult<()> {
+    let (tokens, warnings) = read_lenient("<title>Tom & Jerry; &amp; friends</title>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("title"),
+            text("Tom & Jerry; & friends"),
+            close("title")
+        ]
+    );
+    assert!(warnings.is_empty());
+    Ok(())
+}
+
+#[test]
+fn stray_cdata_end_is_text() -> TestResult<()> {
+    let (tokens, warnings) = read_lenient("<title>Hello ]]> World</title>")?;
+    assert_eq!(
+        tokens,
+        vec![open("title"), text("Hello ]]> World"), close("title")]
+    );
+    let [Error::StrayCDataEnd(span)] = warnings.as_slice() else {
+        panic!("Expected one StrayCDataEnd, got {warnings:?}");
+    };
+    assert_eq!(span.column, 14);
+    Ok(())
+}
+
+#[test]
+fn unquoted_attribute() -> TestResult<()> {
+    let (tokens, warnings) = read_lenient("<a href=x>y</a>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            open("a"),
+            Tokens::ParameterName("href".into()),
+            Tokens::ParameterValue("x".into()),
+            text("y"),
+            close("a"),
+        ]
+    );
+    assert!(matches!(warnings.as_slice(), [Error::UnquotedAttribute(_)]));
+    Ok(())
+}
+
+#[test]
+fn garbage_between_items() -> TestResult<()> {
+    let (tokens, warnings) =
+        read_lenient("<channel><item>A</item> <<garbage & >> ]]> </ <item>B</item></channel>")?;
+    // Whatever the garbage is read as, both items make it through whole
+    let items: Vec<&Tokens> = tokens
+        .iter()
+        .skip_while(|token| **token != open("item"))
+        .collect();
+    assert_eq!(&items[..3], [&open("item"), &text("A"), &close("item")]);
+    let second = tokens.iter().rposition(|token| *token == open("item"));
+    assert_eq!(
+        second.map(|i| &tokens[i..i + 3]),
+        Some(&[open("item"), text("B"), close("item")][..])
+    );
+    assert_eq!(tokens.last(), Some(&close("channel")));
+    assert!(!warnings.is_empty());
+    Ok(())
+}
+
+#[test]
+fn strict_reader_has_no_warnings() {
+    let mut reader = StrReader::new("<item><title>Hello</item>");
+    let tokens = reader.by_ref().collect::<Result<Vec<Tokens>, Error>>();
+    assert!(matches!(tokens, Err(Error::MismatchedClose(..))));
+    assert!(reader.warnings().is_empty());
+}
+
+// Well formed documents read the same either way, without any warnings
+fn test_against_tokenize(test_path: &str) -> TestResult<()> {
+    let mut test_data = String::new();
+    File::open(test_path)?.read_to_string(&mut test_data)?;
+    let expected = tokenize(&mut lex(&test_data)?.into_iter())?;
+
+    let mut reader = Reader::lenient(BufReader::new(File::open(test_path)?));
+    let tokens = reader.by_ref().collect::<Result<Vec<Tokens>, Error>>()?;
+
+    assert_eq!(tokens, expected);
+    assert!(reader.warnings().is_empty(), "{:?}", reader.warnings());
+    Ok(())
+}
+
+#[test]
+fn matches_tokenize() -> TestResult<()> {
+    test_against_tokenize("./tests/test.rss")?;
+    test_against_tokenize("./tests/@sarahgreywolf.rss")?;
+    test_against_tokenize("./tests/Kisserss.rss")?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use greyxml::{lex, tokenize, Error, Lexicals, Tokens};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn tokenize_str(xml: &str) -> Result<Vec<Tokens<'_>>, Error> {
+    let lexed: Vec<Lexicals> = lex(xml)?;
+    tokenize(&mut lexed.into_iter())
+}
+
+#[test]
+fn cdata_is_kept_intact() -> TestResult<()> {
+    let html = "<p class=\"x\">Tom &amp; Jerry]] <br>\n  &lt;3 ] ></p>";
+    let xml = format!("<content:encoded><![CDATA[{html}]]></content:encoded>");
+    let tokens = tokenize_str(&xml)?;
+    assert_eq!(tokens[1], Tokens::CData(html.into()));
+    assert_eq!(tokens.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn comments() -> TestResult<()> {
+    let tokens = tokenize_str("<a><!-- <b>not a node</b> - -- --->Hi<!----></a>")?;
+    assert_eq!(
+        tokens,
+        vec![
+            Tokens::OpenNode("a".into()),
+            Tokens::Comment(" <b>not a node</b> - -- -".into()),
+            Tokens::Text("Hi".into()),
+            Tokens::Comment("".into()),
+            Tokens::CloseNode("a".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn processing_instructions() -> TestResult<()> {
+    let tokens = tokenize_str(
+        r#"<?xml version="1.0" encoding="UTF-8"?><?xml-stylesheet href="a.xsl"?><?empty?><rss />"#,
+    )?;
+    assert_eq!(
+        tokens[..3],
+        [
+            Tokens::ProcessingInstruction("xml".into(), r#"version="1.0" encoding="UTF-8""#.into()),
+            Tokens::ProcessingInstruction("xml-stylesheet".into(), r#"href="a.xsl""#.into()),
+            Tokens::ProcessingInstruction("empty".into(), "".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn doctype_with_internal_subset() -> TestResult<()> {
+    let tokens = tokenize_str(r#"<!DOCTYPE rss [ <!ENTITY gt ">"> <!ENTITY lt "<"> ]><rss />"#)?;
+    assert_eq!(
+        tokens[0],
+        Tokens::Doctype(r#"rss [ <!ENTITY gt ">"> <!ENTITY lt "<"> ]"#.into())
+    );
+    assert_eq!(tokens[1], Tokens::OpenNode("rss".into()));
+    Ok(())
+}
+
+#[test]
+fn markup_characters_in_parameter_values() -> TestResult<()> {
+    let tokens = tokenize_str(r#"<a title="]]> <b> [x] !?">What? [Yes]!</a>"#)?;
+    assert_eq!(
+        tokens,
+        vec![
+            Tokens::OpenNode("a".into()),
+            Tokens::ParameterName("title".into()),
+            Tokens::ParameterValue("]]> <b> [x] !?".into()),
+            Tokens::Text("What? [Yes]!".into()),
+            Tokens::CloseNode("a".into()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn unterminated_markup() {
+    for xml in [
+        "<a><!-- never closed</a>",
+        "<a><![CDATA[ never closed</a>",
+        "<?xml",
+    ] {
+        let err = lex(xml).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedEof(_)), "{xml}: {err:?}");
+    }
+}
@@ -0,0 +1,91 @@
+use greyxml::{lex, tokenize, Tokens, XMLNS_NAMESPACE};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const CONTENT: &str = "http://purl.org/rss/1.0/modules/content/";
+const ATOM: &str = "http://www.w3.org/2005/Atom";
+
+fn open_nodes(xml: &str) -> TestResult<Vec<Tokens<'static>>> {
+    let tokens = tokenize(&mut lex(xml)?.into_iter())?;
+    Ok(tokens
+        .into_iter()
+        .filter(|t| matches!(t, Tokens::OpenNode(_)))
+        .map(Tokens::into_owned)
+        .collect())
+}
+
+#[test]
+fn prefix_is_resolved_whatever_it_is_called() -> TestResult<()> {
+    for prefix in ["content", "c"] {
+        let xml = format!(
+            r#"<rss xmlns:{prefix}="{CONTENT}"><item><{prefix}:encoded>Hi</{prefix}:encoded></item></rss>"#
+        );
+        let tokens = tokenize(&mut lex(&xml)?.into_iter())?;
+        let Some(Tokens::OpenNode(open)) = tokens.get(4) else {
+            panic!("Expected an OpenNode, got {:?}", tokens.get(4));
+        };
+        assert!(open.is(CONTENT, "encoded"));
+        assert_eq!(open.prefix(), Some(prefix));
+        let Some(Tokens::CloseNode(close)) = tokens.get(6) else {
+            panic!("Expected a CloseNode, got {:?}", tokens.get(6));
+        };
+        assert!(close.is(CONTENT, "encoded"));
+    }
+    Ok(())
+}
+
+#[test]
+fn default_namespace() -> TestResult<()> {
+    let nodes = open_nodes(&format!(
+        r#"<feed xmlns="{ATOM}"><title>T</title><x xmlns=""><y /></x></feed>"#
+    ))?;
+    let names: Vec<_> = nodes
+        .iter()
+        .map(|t| match t {
+            Tokens::OpenNode(name) => name.namespace.as_deref(),
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(names, vec![Some(ATOM), Some(ATOM), None, None]);
+    Ok(())
+}
+
+#[test]
+fn declarations_go_out_of_scope() -> TestResult<()> {
+    let nodes = open_nodes(&format!(
+        r#"<rss><a xmlns:atom="{ATOM}"><atom:link /></a><atom:link /></rss>"#
+    ))?;
+    let Tokens::OpenNode(inner) = &nodes[2] else {
+        unreachable!()
+    };
+    let Tokens::OpenNode(outer) = &nodes[3] else {
+        unreachable!()
+    };
+    assert!(inner.is(ATOM, "link"));
+    assert_eq!(outer.namespace, None);
+    assert_eq!(outer.local_name(), "link");
+    Ok(())
+}
+
+#[test]
+fn parameter_names() -> TestResult<()> {
+    let xml = format!(r#"<a xmlns="{ATOM}" xmlns:x="urn:x" x:y="1" z="2"></a>"#);
+    let tokens = tokenize(&mut lex(&xml)?.into_iter())?;
+    let parameters: Vec<_> = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Tokens::ParameterName(name) => Some(name.namespace.as_deref()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        parameters,
+        vec![
+            Some(XMLNS_NAMESPACE),
+            Some(XMLNS_NAMESPACE),
+            Some("urn:x"),
+            None
+        ]
+    );
+    Ok(())
+}
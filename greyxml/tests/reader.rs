@@ -0,0 +1,126 @@
+use greyxml::{lex, tokenize, tokenize_html, Error, Reader, StrReader, Tokens};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn test_against_tokenize(test_path: &str) -> TestResult<()> {
+    let mut test_data = String::new();
+    File::open(test_path)?.read_to_string(&mut test_data)?;
+    let expected = tokenize(&mut lex(&test_data)?.into_iter())?;
+
+    let reader = Reader::new(BufReader::new(File::open(test_path)?));
+    let tokens = reader.collect::<Result<Vec<Tokens>, Error>>()?;
+
+    assert_eq!(tokens, expected);
+    Ok(())
+}
+
+fn test_against_tokenize_html(test_path: &str) -> TestResult<()> {
+    let mut test_data = String::new();
+    File::open(test_path)?.read_to_string(&mut test_data)?;
+    let expected = tokenize_html(&mut lex(&test_data)?.into_iter())?;
+
+    let reader = Reader::html(BufReader::new(File::open(test_path)?));
+    let tokens = reader.collect::<Result<Vec<Tokens>, Error>>()?;
+
+    assert_eq!(tokens, expected);
+    Ok(())
+}
+
+#[test]
+fn matches_tokenize() -> TestResult<()> {
+    test_against_tokenize("./tests/test.rss")?;
+    test_against_tokenize("./tests/@sarahgreywolf.rss")?;
+    test_against_tokenize("./tests/Kisserss.rss")?;
+    test_against_tokenize_html("./tests/example.html")?;
+    Ok(())
+}
+
+// Hands out the start of a document then fails, like a dropped connection
+struct Truncated<'a>(&'a [u8]);
+
+impl Read for Truncated<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::ConnectionReset, "gone"));
+        }
+        let length = self.0.len().min(buf.len());
+        buf[..length].copy_from_slice(&self.0[..length]);
+        self.0 = &self.0[length..];
+        Ok(length)
+    }
+}
+
+#[test]
+fn tokens_are_produced_lazily() {
+    let input = Truncated(b"<rss version=\"2.0\"><channel><title>Hello</title>");
+    let mut reader = Reader::new(BufReader::new(input));
+
+    assert_eq!(reader.next(), Some(Ok(Tokens::OpenNode("rss".into()))));
+    assert_eq!(
+        reader.next(),
+        Some(Ok(Tokens::ParameterName("version".into())))
+    );
+    assert_eq!(
+        reader.next(),
+        Some(Ok(Tokens::ParameterValue("2.0".into())))
+    );
+    assert_eq!(reader.next(), Some(Ok(Tokens::OpenNode("channel".into()))));
+    assert_eq!(reader.next(), Some(Ok(Tokens::OpenNode("title".into()))));
+    assert_eq!(reader.next(), Some(Ok(Tokens::Text("Hello".into()))));
+    assert_eq!(reader.next(), Some(Ok(Tokens::CloseNode("title".into()))));
+    assert!(matches!(
+        reader.next(),
+        Some(Err(Error::Io(io::ErrorKind::ConnectionReset, _)))
+    ));
+    assert_eq!(reader.next(), None);
+}
+
+#[test]
+fn invalid_utf8() {
+    let reader = Reader::new(&b"<title>Caf\xe9</title>"[..]);
+    let result = reader.collect::<Result<Vec<Tokens>, Error>>();
+    assert!(matches!(
+        result,
+        Err(Error::Io(io::ErrorKind::InvalidData, _))
+    ));
+}
+
+#[test]
+fn multibyte_characters() -> TestResult<()> {
+    let reader = Reader::new("<title>Caf\u{e9} \u{1f43a}</title>".as_bytes());
+    let tokens = reader.collect::<Result<Vec<Tokens>, Error>>()?;
+    assert_eq!(tokens[1], Tokens::Text("Caf\u{e9} \u{1f43a}".into()));
+    Ok(())
+}
+
+#[test]
+fn start_tag_spans() -> TestResult<()> {
+    let xml = "<rss>\n  <channel a=\"b\">\n    <title>Feed</title>\n  </channel>\n</rss>";
+    let mut reader = Reader::new(xml.as_bytes());
+    let mut str_reader = StrReader::new(xml);
+    assert_eq!(reader.span(), None);
+    let mut spans = vec![];
+    while let Some(token) = reader.next() {
+        assert_eq!(str_reader.next().transpose()?.as_ref(), Some(&token?));
+        assert_eq!(str_reader.span(), reader.span());
+        spans.push(reader.span().map(|span| (span.row, span.column)));
+    }
+    // Each stays the last start tag's until the next one is read
+    assert_eq!(
+        spans,
+        [
+            Some((1, 2)),
+            Some((2, 4)),
+            Some((2, 4)),
+            Some((2, 4)),
+            Some((3, 6)),
+            Some((3, 6)),
+            Some((3, 6)),
+            Some((3, 6)),
+            Some((3, 6)),
+        ]
+    );
+    Ok(())
+}
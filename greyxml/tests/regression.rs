@@ -1,10 +1,18 @@
-use greyxml::{lex, tokenize};
+use greyxml::{lex, tokenize, tokenize_html, Tokens};
 use std::fs::File;
 use std::io::Read;
 
 type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
 
 fn test_against_file(test_path: &str, test_output_path: &str) -> TestResult<()> {
+    test_against_file_with(test_path, test_output_path, false)
+}
+
+fn test_against_file_with(
+    test_path: &str,
+    test_output_path: &str,
+    html: bool,
+) -> TestResult<()> {
     let mut test_file = File::open(test_path)?;
     let mut test_output = File::open(test_output_path)?;
     let mut test_data = String::new();
@@ -14,7 +22,11 @@ fn test_against_file(test_path: &str, test_output_path: &str) -> TestResult<()>
 
     let lexed = lex(&test_data)?;
     let mut iter = lexed.into_iter();
-    let tokens = tokenize(&mut iter)?;
+    let tokens = if html {
+        tokenize_html(&mut iter)?
+    } else {
+        tokenize(&mut iter)?
+    };
 
     assert_eq!(format!("{:?}", tokens), output);
 
@@ -38,12 +50,66 @@ fn sarah_tech_rss() -> TestResult<()> {
 
 #[test]
 fn repo_rss() -> TestResult<()> {
-    test_against_file("./tests/kisserss.rss", "./tests/kisserss.rss.output")?;
+    test_against_file("./tests/Kisserss.rss", "./tests/kisserss.rss.output")?;
+    Ok(())
+}
+
+// The repo's feed as tokens, for checking more closely than the golden file
+fn repo_rss_tokens() -> TestResult<Vec<Tokens<'static>>> {
+    let mut test_data = String::new();
+    File::open("./tests/Kisserss.rss")?.read_to_string(&mut test_data)?;
+    let tokens = tokenize(&mut lex(&test_data)?.into_iter())?;
+    Ok(tokens.into_iter().map(Tokens::into_owned).collect())
+}
+
+#[test]
+fn repo_rss_namespaces() -> TestResult<()> {
+    const CONTENT: &str = "http://purl.org/rss/1.0/modules/content/";
+    for token in repo_rss_tokens()? {
+        let (Tokens::OpenNode(name) | Tokens::CloseNode(name)) = token else {
+            continue;
+        };
+        if name.qualified.starts_with("content:") {
+            assert!(name.is(CONTENT, "encoded"), "{name:?}");
+        } else {
+            // Unprefixed names stay out of any namespace
+            assert_eq!(name.namespace, None, "{name:?}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn repo_rss_markup() -> TestResult<()> {
+    let tokens = repo_rss_tokens()?;
+    let Some(Tokens::ProcessingInstruction(target, _)) = tokens.first() else {
+        panic!("Expected the xml declaration, got {:?}", tokens.first());
+    };
+    assert_eq!(target, "xml");
+    // Every content:encoded holds a CDATA section and nothing else
+    let mut encoded = 0;
+    for window in tokens.windows(3) {
+        if let [Tokens::OpenNode(open), text, Tokens::CloseNode(_)] = window {
+            if open.qualified == "content:encoded" {
+                assert!(matches!(text, Tokens::CData(_)), "{text:?}");
+                encoded += 1;
+            }
+        }
+    }
+    assert_eq!(encoded, 17);
+    assert!(matches!(
+        tokens.iter().find(|token| matches!(token, Tokens::CData(_))),
+        Some(Tokens::CData(text)) if text == "Read RSS feed via URL."
+    ));
     Ok(())
 }
 
 #[test]
 fn html() -> TestResult<()> {
-    test_against_file("./tests/example.html", "./tests/example.html.output")?;
+    test_against_file_with(
+        "./tests/example.html",
+        "./tests/example.html.output",
+        true,
+    )?;
     Ok(())
 }
@@ -1,4 +1,4 @@
-use greyxml::{lex, tokenize, Lexicals};
+use greyxml::{lex, Lexicals};
 
 type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
 
@@ -11,19 +11,19 @@ fn test_span(
     match lexical {
         Lexicals::OpenArrow(span)
         | Lexicals::CloseArrow(span)
-        | Lexicals::ImmediateClose(span)
         | Lexicals::CloseFSlash(span)
-        | Lexicals::LeftSquareBracket(span)
-        | Lexicals::RightSquareBracket(span)
-        | Lexicals::Bang(span)
         | Lexicals::Equals(span)
         | Lexicals::DoubleQuote(span)
-        | Lexicals::Question(span) => {
+        | Lexicals::SingleQuote(span) => {
             assert_eq!(span.row, expected_row);
             assert_eq!(span.column, expected_column);
             assert_eq!(span.length, expected_length);
         }
-        Lexicals::Text(_, span) => {
+        Lexicals::Text(_, span)
+        | Lexicals::CData(_, span)
+        | Lexicals::Comment(_, span)
+        | Lexicals::ProcessingInstruction(_, span)
+        | Lexicals::Doctype(_, span) => {
             assert_eq!(span.row, expected_row);
             assert_eq!(span.column, expected_column);
             assert_eq!(span.length, expected_length);
@@ -76,4 +76,13 @@ fn test_multiline_span() -> TestResult<()> {
     test_span(&lexed[15], 4, 7, 1);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_short_text_span() -> TestResult<()> {
+    // Text shorter than the tag's own span used to underflow the length
+    let lexed = lex("z<a/>")?;
+    assert!(matches!(&lexed[0], Lexicals::Text(text, _) if text == "z"));
+
+    Ok(())
+}
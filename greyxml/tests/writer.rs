@@ -0,0 +1,151 @@
+use greyxml::{lex, parse_document, tokenize, tokenize_html, Name, Tokens, Writer};
+use std::fs::File;
+use std::io::Read;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const CONTENT: &str = "http://purl.org/rss/1.0/modules/content/";
+
+fn read_tokens(xml: &str) -> TestResult<Vec<Tokens<'_>>> {
+    Ok(tokenize(&mut lex(xml)?.into_iter())?)
+}
+
+fn write_tokens(tokens: &[Tokens], pretty: bool) -> TestResult<String> {
+    let mut writer = if pretty {
+        Writer::pretty(vec![], 2)
+    } else {
+        Writer::new(vec![])
+    };
+    writer.write_tokens(tokens)?;
+    Ok(String::from_utf8(writer.finish()?)?)
+}
+
+fn test_round_trip(test_path: &str) -> TestResult<()> {
+    let mut test_data = String::new();
+    File::open(test_path)?.read_to_string(&mut test_data)?;
+    let expected = read_tokens(&test_data)?;
+    for pretty in [false, true] {
+        let written = write_tokens(&expected, pretty)?;
+        assert_eq!(read_tokens(&written)?, expected, "{test_path}:\n{written}");
+    }
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> TestResult<()> {
+    test_round_trip("./tests/test.rss")?;
+    test_round_trip("./tests/@sarahgreywolf.rss")?;
+    test_round_trip("./tests/Kisserss.rss")?;
+    Ok(())
+}
+
+// The balanced tokens from HTML are written back out as well formed XML
+#[test]
+fn html_round_trip() -> TestResult<()> {
+    let mut test_data = String::new();
+    File::open("./tests/example.html")?.read_to_string(&mut test_data)?;
+    let expected = tokenize_html(&mut lex(&test_data)?.into_iter())?;
+    for pretty in [false, true] {
+        let written = write_tokens(&expected, pretty)?;
+        assert_eq!(read_tokens(&written)?, expected, "{written}");
+    }
+    Ok(())
+}
+
+#[test]
+fn escaping() -> TestResult<()> {
+    let tokens = vec![
+        Tokens::OpenNode("a".into()),
+        Tokens::ParameterName("title".into()),
+        Tokens::ParameterValue("\"Fish\" & <Chips>".into()),
+        Tokens::Text("1 < 2 && 3 > 2".into()),
+        Tokens::CloseNode("a".into()),
+    ];
+    let written = write_tokens(&tokens, false)?;
+    assert_eq!(
+        written,
+        r#"<a title="&quot;Fish&quot; &amp; &lt;Chips&gt;">1 &lt; 2 &amp;&amp; 3 &gt; 2</a>"#
+    );
+    assert_eq!(read_tokens(&written)?, tokens);
+    Ok(())
+}
+
+#[test]
+fn pretty_and_compact() -> TestResult<()> {
+    let tokens = read_tokens(
+        r#"<?xml version="1.0"?><rss><channel><title>T</title><item /></channel></rss>"#,
+    )?;
+    assert_eq!(
+        write_tokens(&tokens, false)?,
+        r#"<?xml version="1.0"?><rss><channel><title>T</title><item /></channel></rss>"#
+    );
+    assert_eq!(
+        write_tokens(&tokens, true)?,
+        "<?xml version=\"1.0\"?>\n<rss>\n  <channel>\n    <title>T</title>\n    <item />\n  </channel>\n</rss>\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_namespaces_are_declared() -> TestResult<()> {
+    let encoded = Name {
+        qualified: "content:encoded".into(),
+        namespace: Some(CONTENT.into()),
+    };
+    let tokens = vec![
+        Tokens::OpenNode("item".into()),
+        Tokens::OpenNode(encoded.clone()),
+        Tokens::Text("Hi".into()),
+        Tokens::CloseNode(encoded.clone()),
+        Tokens::OpenNode(encoded.clone()),
+        Tokens::CloseNode(encoded),
+        Tokens::CloseNode("item".into()),
+    ];
+    let written = write_tokens(&tokens, false)?;
+    assert_eq!(
+        written,
+        format!(
+            r#"<item><content:encoded xmlns:content="{CONTENT}">Hi</content:encoded><content:encoded xmlns:content="{CONTENT}" /></item>"#
+        )
+    );
+    let read = read_tokens(&written)?;
+    let Some(Tokens::OpenNode(open)) = read.get(1) else {
+        panic!("Expected an OpenNode, got {:?}", read.get(1));
+    };
+    assert!(open.is(CONTENT, "encoded"));
+    Ok(())
+}
+
+#[test]
+fn markup() -> TestResult<()> {
+    let xml =
+        r#"<?xml version="1.0"?><!DOCTYPE rss><!-- A comment --><rss><![CDATA[<b>Hi</b>]]></rss>"#;
+    let tokens = read_tokens(xml)?;
+    assert_eq!(write_tokens(&tokens, false)?, xml);
+    Ok(())
+}
+
+#[test]
+fn cdata() -> TestResult<()> {
+    let mut writer = Writer::new(vec![]);
+    writer.write_token(&Tokens::OpenNode("p".into()))?;
+    writer.write_cdata("<b>a]]>b</b>")?;
+    writer.write_token(&Tokens::CloseNode("p".into()))?;
+    assert_eq!(
+        String::from_utf8(writer.finish()?)?,
+        "<p><![CDATA[<b>a]]]]><![CDATA[>b</b>]]></p>"
+    );
+    Ok(())
+}
+
+#[test]
+fn document_round_trip() -> TestResult<()> {
+    let mut test_data = String::new();
+    File::open("./tests/@sarahgreywolf.rss")?.read_to_string(&mut test_data)?;
+    let document = parse_document(test_data.as_bytes())?;
+    let mut writer = Writer::pretty(vec![], 4);
+    writer.write_document(&document)?;
+    let written = writer.finish()?;
+    assert_eq!(parse_document(written.as_slice())?, document);
+    Ok(())
+}
@@ -1,10 +1,18 @@
-use std::iter::Peekable;
+use std::io::BufRead;
 
-use greyxml::{lex, tokenize, Tokens};
+use greyxml::{Name, Reader, Tokens};
 
-use crate::elements::{self, Element};
+use crate::date::Date;
+use crate::dublin_core::DublinCore;
+use crate::elements::{
+    is_atom, is_dublin_core, push, read_document, skip, Document, Element, FromTokens, Spanned,
+    TokenStream,
+};
+use crate::error::FeedError;
+use crate::media::Media;
+use crate::namespace;
 
-type AtomResult<T> = Result<T, Box<dyn std::error::Error>>;
+type AtomResult<T> = Result<T, FeedError>;
 
 // https://datatracker.ietf.org/doc/html/rfc4287#section-4.1.1
 #[derive(Debug, Default)]
@@ -14,44 +22,39 @@ pub struct Feed {
 }
 
 impl Feed {
-    pub fn serialize(input: &str) -> AtomResult<Feed> {
-        let lexed = lex(input.into())?;
-        let tokens = tokenize(&mut lexed.into_iter())?;
-
-        let mut tokens_iter = tokens.into_iter().peekable();
+    pub fn serialize<R: BufRead>(reader: R) -> AtomResult<Feed> {
+        read_document(Reader::new(reader), false).map(|(feed, _)| feed)
+    }
 
-        let Some(feed) = tokens_iter.find(|t| t == &Tokens::OpenNode("feed".into())) else {
-            //FIXME: Handle error properly
-            panic!("Could not find rss node");
-        };
+    // Reads feeds too broken to be XML as best it can, along with everything
+    // that had to be recovered from to read it
+    pub fn serialize_lenient<R: BufRead>(reader: R) -> AtomResult<(Feed, Vec<FeedError>)> {
+        read_document(Reader::lenient(reader), true)
+    }
+}
 
-        let Some(token) = tokens_iter.next() else {
-            panic!("No more tokens?");
+impl Document for Feed {
+    fn serialize_tokens<'a, I>(tokens_iter: &mut TokenStream<I>) -> AtomResult<Feed>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        let Some(Tokens::OpenNode(feed)) = tokens_iter.find(|t| match t {
+            // Feeds missing their xmlns are common enough to allow
+            Tokens::OpenNode(name) => name.is(namespace::ATOM, "feed") || name == "feed",
+            _ => false,
+        }) else {
+            return Err(FeedError::Missing("feed".into(), None));
         };
 
-        let mut specs = vec![];
-
-        while let Some(token) = tokens_iter.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    tokens_iter.next();
-                    if let Some(Tokens::ParameterValue(value)) = tokens_iter.next() {
-                        specs.push((name.clone(), value));
-                    }
-                }
-                _ => {
-                    break;
-                }
-            }
-        }
-
-        let inner = Inner::serialize(&mut tokens_iter);
+        let feed = Element::<Inner>::read(feed, tokens_iter)?;
 
         Ok(Feed {
-            specs: if specs.is_empty() { None } else { Some(specs) },
-            // FIXME: Don't use `unwrap()` here
-            contents: inner,
+            specs: if feed.attributes.is_empty() {
+                None
+            } else {
+                Some(feed.attributes)
+            },
+            contents: feed.data,
         })
     }
 }
@@ -87,125 +90,37 @@ pub struct Inner {
     // MUST contain exactly one
     pub title: Element<String>,
     // MUST contain exactly one
-    pub updated: Element<String>,
+    pub updated: Element<Date>,
     pub entries: Vec<Element<Option<Entry>>>,
+    pub dublin_core: Option<DublinCore>,
 }
-impl Inner {
-    pub fn serialize<I>(tokens: &mut Peekable<I>) -> Self
+impl FromTokens for Inner {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> AtomResult<()>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let mut attributes = vec![];
-
-        let mut inner = Inner::default();
-
-        let mut looking_for_attributes = true;
-
-        let mut authors = vec![];
-        let mut categories = vec![];
-        let mut contributors = vec![];
-        let mut links = vec![];
-        let mut entries = vec![];
-
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(new_node) => {
-                    looking_for_attributes = false;
-                    match new_node.as_str() {
-                        "author" => authors
-                            .push(Element::<Person>::serialize(tokens.next().unwrap(), tokens)),
-                        "category" => categories.push(Element::<Category>::serialize(
-                            tokens.next().unwrap(),
-                            tokens,
-                        )),
-                        "contributor" => contributors
-                            .push(Element::<Person>::serialize(tokens.next().unwrap(), tokens)),
-                        "generator" => {
-                            inner.generator =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "icon" => {
-                            inner.icon =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "id" => {
-                            inner.id = Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                        }
-                        "link" => {
-                            links.push(Element::<Link>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "logo" => {
-                            inner.logo =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "rights" => {
-                            inner.rights =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "subtitle" => {
-                            inner.subtitle =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "title" => {
-                            inner.title =
-                                Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                        }
-                        "updated" => {
-                            inner.updated =
-                                Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                        }
-                        "entry" => entries.push(Element::<Option<Entry>>::serialize(
-                            tokens.next().unwrap(),
-                            tokens,
-                        )),
-                        all @ _ => {
-                            println!("Unimplemented: {all:?}");
-                        }
-                    }
-                }
-                Tokens::CloseNode(close) => {
-                    if close == "feed" {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
-            }
-            tokens.next();
+        match node.local_name() {
+            _ if is_dublin_core(&node) => self
+                .dublin_core
+                .get_or_insert_with(Default::default)
+                .child(node, tokens)?,
+            _ if !is_atom(&node) => skip(&node, tokens)?,
+            "author" => push(&mut self.authors, Element::read(node, tokens)?),
+            "category" => push(&mut self.categories, Element::read(node, tokens)?),
+            "contributor" => push(&mut self.contributors, Element::read(node, tokens)?),
+            "generator" => self.generator = Some(Element::read(node, tokens)?),
+            "icon" => self.icon = Some(Element::read(node, tokens)?),
+            "id" => self.id = Element::read(node, tokens)?,
+            "link" => push(&mut self.links, Element::read(node, tokens)?),
+            "logo" => self.logo = Some(Element::read(node, tokens)?),
+            "rights" => self.rights = Some(Element::read(node, tokens)?),
+            "subtitle" => self.subtitle = Some(Element::read(node, tokens)?),
+            "title" => self.title = Element::read(node, tokens)?,
+            "updated" => self.updated = Element::read(node, tokens)?,
+            "entry" => self.entries.push(Element::read(node, tokens)?),
+            _ => skip(&node, tokens)?,
         }
-
-        inner.authors = if authors.is_empty() {
-            None
-        } else {
-            Some(authors)
-        };
-        inner.categories = if categories.is_empty() {
-            None
-        } else {
-            Some(categories)
-        };
-        inner.contributors = if contributors.is_empty() {
-            None
-        } else {
-            Some(contributors)
-        };
-        inner.links = if links.is_empty() { None } else { Some(links) };
-        inner.entries = entries;
-
-        inner
+        Ok(())
     }
 }
 
@@ -221,11 +136,35 @@ pub struct Entry {
     // MUST contain at least one atom:link element with a rel attribute
     // value of "alternate".
     pub links: Option<Vec<Element<Link>>>,
-    pub published: Option<Element<String>>,
+    pub published: Option<Element<Date>>,
     pub rights: Option<Element<String>>,
     pub summary: Option<Element<String>>,
     pub title: Element<String>,
-    pub updated: Element<String>,
+    pub updated: Element<Date>,
+    // YouTube puts each video's description and thumbnail in here
+    pub media: Option<Media>,
+    pub dublin_core: Option<DublinCore>,
+}
+
+impl Entry {
+    // The first of the entry's own authors, or its dc:creator
+    pub fn author(&self) -> Option<&str> {
+        match self.authors.as_ref().and_then(|authors| authors.first()) {
+            Some(author) => Some(&author.data.name.data),
+            None => self.dublin_core.as_ref()?.creator(),
+        }
+    }
+
+    // When the entry was first published, or else last updated
+    pub fn date(&self) -> Option<&Date> {
+        if let Some(ref published) = self.published {
+            return Some(&published.data);
+        }
+        if !self.updated.data.text.is_empty() {
+            return Some(&self.updated.data);
+        }
+        self.dublin_core.as_ref()?.date()
+    }
 }
 
 #[derive(Debug, Default)]
@@ -0,0 +1,146 @@
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+// A date as the feed wrote it, along with the moment it names when it could
+// be made sense of. RSS uses RFC 822 dates and Atom RFC 3339 ones, but feeds
+// get both wrong often enough that `text` is what gets shown.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Date {
+    pub text: String,
+    pub time: Option<DateTime<FixedOffset>>,
+}
+
+impl Date {
+    pub fn parse(text: &str) -> Date {
+        let trimmed = text.trim();
+        Date {
+            text: text.to_string(),
+            time: parse_rfc3339(trimmed).or_else(|| parse_rfc822(trimmed)),
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+// RFC 3339 and the looser ISO 8601 dates written in its place: a date alone,
+// a time without seconds, or no offset, which is taken as UTC
+fn parse_rfc3339(text: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time);
+    }
+    for format in ["%Y-%m-%dT%H:%M%#z", "%Y-%m-%d %H:%M:%S%#z"] {
+        if let Ok(time) = DateTime::parse_from_str(text, format) {
+            return Some(time);
+        }
+    }
+    let text = text.strip_suffix(['Z', 'z']).unwrap_or(text);
+    let utc = FixedOffset::east_opt(0)?;
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+    ] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(text, format) {
+            return utc.from_local_datetime(&time).single();
+        }
+    }
+    let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+    utc.from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .single()
+}
+
+// RFC 822 as RFC 2822 reads it, such as `Mon, 02 Jan 2006 15:04:05 -0700`.
+// The day of the week is optional and never checked, and feeds are allowed
+// two digit years, full month names, a time without seconds, named zones and
+// no zone at all.
+fn parse_rfc822(text: &str) -> Option<DateTime<FixedOffset>> {
+    let mut fields = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|field| !field.is_empty())
+        .peekable();
+    fields.next_if(|field| field.starts_with(|c: char| c.is_ascii_alphabetic()));
+
+    let day = fields.next()?.parse().ok()?;
+    let month = month(fields.next()?)?;
+    let year = match fields.next()? {
+        year if year.len() == 2 => {
+            let year: i32 = year.parse().ok()?;
+            // RFC 2822 section 4.3
+            if year < 50 {
+                year + 2000
+            } else {
+                year + 1900
+            }
+        }
+        year => year.parse().ok()?,
+    };
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+
+    let mut clock = fields.next()?.split(':');
+    let hour = clock.next()?.parse().ok()?;
+    let minute = clock.next()?.parse().ok()?;
+    let second = match clock.next() {
+        Some(second) => second.parse().ok()?,
+        None => 0,
+    };
+    let time = NaiveTime::from_hms_opt(hour, minute, second)?;
+
+    let offset = match fields.next() {
+        Some(zone) => zone_offset(zone)?,
+        None => 0,
+    };
+    FixedOffset::east_opt(offset)?
+        .from_local_datetime(&date.and_time(time))
+        .single()
+}
+
+fn month(name: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let short = name.get(..3)?.to_ascii_lowercase();
+    let month = MONTHS.iter().position(|month| *month == short)?;
+    Some(month as u32 + 1)
+}
+
+// Seconds east of UTC, from `+hhmm`, `+hh:mm` or one of the zone names RFC 822
+// allows. Military zones other than Z were defined backwards in RFC 822 so
+// RFC 2822 says to read them as UTC.
+fn zone_offset(zone: &str) -> Option<i32> {
+    let sign = match zone.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return named_zone(zone).map(|hours| hours * 3600),
+    };
+    let digits = zone[1..].replace(':', "");
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+// Hours east of UTC. Names other than RFC 822's, such as CEST, mean different
+// things in different places and are left unread rather than guessed at.
+fn named_zone(zone: &str) -> Option<i32> {
+    let hours = match zone.to_ascii_uppercase().as_str() {
+        "UT" | "UTC" | "GMT" | "Z" => 0,
+        "EST" => -5,
+        "EDT" => -4,
+        "CST" => -6,
+        "CDT" => -5,
+        "MST" => -7,
+        "MDT" => -6,
+        "PST" => -8,
+        "PDT" => -7,
+        zone if zone.len() == 1 && zone.chars().all(|c| c.is_ascii_alphabetic()) => 0,
+        _ => return None,
+    };
+    Some(hours)
+}
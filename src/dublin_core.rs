@@ -0,0 +1,33 @@
+use crate::date::Date;
+use crate::elements::Element;
+
+// https://www.dublincore.org/specifications/dublin-core/dces/
+// WordPress and Substack among others give an item's author and date in
+// dc:creator and dc:date rather than <author> and <pubDate>. Any of these can
+// be repeated, but only the ones with more than one in practice are lists.
+#[derive(Debug, Default)]
+pub struct DublinCore {
+    pub title: Option<Element<String>>,
+    pub creators: Option<Vec<Element<String>>>,
+    pub subjects: Option<Vec<Element<String>>>,
+    pub description: Option<Element<String>>,
+    pub publisher: Option<Element<String>>,
+    pub contributors: Option<Vec<Element<String>>>,
+    // Usually a W3CDTF date such as 2023-01-02T15:04:05Z
+    pub date: Option<Element<Date>>,
+    pub identifier: Option<Element<String>>,
+    pub language: Option<Element<String>>,
+    pub rights: Option<Element<String>>,
+}
+
+impl DublinCore {
+    // The first creator, who is the author where there is only one
+    pub fn creator(&self) -> Option<&str> {
+        let creator = self.creators.as_ref()?.first()?;
+        Some(creator.data.as_str())
+    }
+
+    pub fn date(&self) -> Option<&Date> {
+        self.date.as_ref().map(|date| &date.data)
+    }
+}
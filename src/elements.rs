@@ -1,8 +1,149 @@
-use greyxml::Tokens;
+use greyxml::{Name, Reader, Span, Tokens};
+use std::io::BufRead;
 use std::iter::Peekable;
+use std::str::FromStr;
 
 use crate::atom;
-use crate::rss::{Channel, EncodedContent, Image, Item};
+use crate::date::Date;
+use crate::dublin_core::DublinCore;
+use crate::error::FeedError;
+use crate::media::{Content, Details, Group, Media, Player, Thumbnail};
+use crate::namespace;
+use crate::podcast::{Chapters, Duration, Episode, Image as PodcastImage, Show, Transcript};
+use crate::rss::{
+    Channel, Cloud, Days, Enclosure, EncodedContent, Image, Item, Rdf, Sequence, SkipDays,
+    SkipHours, TextInput,
+};
+
+type ElementResult<T> = Result<T, FeedError>;
+
+// A token and, for an OpenNode, where its start tag is in the document
+pub type Spanned<'a> = (Tokens<'a>, Option<Span>);
+
+// The tokens of a document as its elements are read from them. Reading
+// leniently, an element that can't be read is left out and what was wrong
+// with it kept as a warning, rather than failing the whole feed over it.
+pub struct TokenStream<I: Iterator> {
+    tokens: Peekable<I>,
+    lenient: bool,
+    warnings: Vec<FeedError>,
+    // Where the start tag of the last OpenNode taken is
+    span: Option<Span>,
+}
+
+impl<'a, I: Iterator<Item = Spanned<'a>>> TokenStream<I> {
+    pub fn new(tokens: I) -> TokenStream<I> {
+        TokenStream {
+            tokens: tokens.peekable(),
+            lenient: false,
+            warnings: vec![],
+            span: None,
+        }
+    }
+
+    pub fn lenient(tokens: I) -> TokenStream<I> {
+        TokenStream {
+            lenient: true,
+            ..TokenStream::new(tokens)
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<&Tokens<'a>> {
+        self.tokens.peek().map(|(token, _)| token)
+    }
+
+    pub fn next_if(&mut self, func: impl FnOnce(&Tokens<'a>) -> bool) -> Option<Tokens<'a>> {
+        let spanned = self.tokens.next_if(|(token, _)| func(token))?;
+        Some(self.take(spanned))
+    }
+
+    // Where the element whose start tag was taken last starts, which is the
+    // one being read when called before any of its children are
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    // Remembers where each OpenNode starts as it is taken
+    fn take(&mut self, (token, span): Spanned<'a>) -> Tokens<'a> {
+        if let Tokens::OpenNode(_) = token {
+            self.span = span;
+        }
+        token
+    }
+
+    // What was recovered from, in the order it was found
+    pub fn into_warnings(self) -> Vec<FeedError> {
+        self.warnings
+    }
+
+    // Running out of tokens leaves nothing to carry on reading
+    fn recover(&mut self, e: FeedError) -> ElementResult<()> {
+        if self.lenient && !matches!(e, FeedError::Unclosed(..)) {
+            self.warnings.push(e);
+            Ok(())
+        } else {
+            Err(e)
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = Spanned<'a>>> Iterator for TokenStream<I> {
+    type Item = Tokens<'a>;
+
+    fn next(&mut self) -> Option<Tokens<'a>> {
+        let spanned = self.tokens.next()?;
+        Some(self.take(spanned))
+    }
+}
+
+// A whole feed, read from the tokens of a document before its root element
+pub(crate) trait Document: Sized {
+    fn serialize_tokens<'a, I>(tokens: &mut TokenStream<I>) -> ElementResult<Self>
+    where
+        I: Iterator<Item = Spanned<'a>>;
+}
+
+// Reads a feed from `reader`, then the rest of the document so one broken
+// after the feed is still reported. The warnings are whatever the reader and
+// the elements recovered from, when reading leniently.
+pub(crate) fn read_document<D: Document, R: BufRead>(
+    mut reader: Reader<R>,
+    lenient: bool,
+) -> ElementResult<(D, Vec<FeedError>)> {
+    let mut error = None;
+    let tokens = std::iter::from_fn(|| match reader.next()? {
+        Ok(token) => {
+            let span = match token {
+                Tokens::OpenNode(_) => reader.span().cloned(),
+                _ => None,
+            };
+            Some((token, span))
+        }
+        Err(e) => {
+            error = Some(e);
+            None
+        }
+    });
+    let mut tokens = if lenient {
+        TokenStream::lenient(tokens)
+    } else {
+        TokenStream::new(tokens)
+    };
+    let document = D::serialize_tokens(&mut tokens);
+    tokens.by_ref().for_each(drop);
+    let element_warnings = tokens.into_warnings();
+    if let Some(e) = error {
+        return Err(e.into());
+    }
+    let mut warnings: Vec<FeedError> = reader
+        .warnings()
+        .iter()
+        .cloned()
+        .map(FeedError::from)
+        .collect();
+    warnings.extend(element_warnings);
+    Ok((document?, warnings))
+}
 
 #[derive(Debug, Default)]
 pub struct Element<T: Default> {
@@ -11,851 +152,748 @@ pub struct Element<T: Default> {
     pub data: T,
 }
 
-impl Element<String> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
+// What an element's content is read into. `Element::read` collects the
+// attributes and finds the end tag, a type only says which of its fields each
+// child element and any text goes to. Whatever `child` is given it has to read
+// to the end, and children it has no field for are skipped whole.
+pub trait FromTokens: Default {
+    // Reads the child element `node` into its field with `Element::read`, or
+    // passes over it with `skip`
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
-
-        let mut content = String::new();
-        let mut attributes = vec![];
-        let mut looking_for_attributes = true;
+        skip(&node, tokens)
+    }
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    }
-                }
-                Tokens::Text(text) => {
-                    looking_for_attributes = false;
-                    content = text;
-                }
-                Tokens::CloseNode(c_node) => {
-                    looking_for_attributes = false;
-                    if node == c_node {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
-            }
-            tokens.next();
-        }
+    // Text or CDATA directly inside the element `node`
+    fn text(&mut self, node: &Name, text: &str) -> ElementResult<()> {
+        let _ = (node, text);
+        Ok(())
+    }
 
-        Self {
-            name: node.clone(),
-            attributes,
-            data: content,
-        }
+    // Called once the end tag has been read
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        let _ = attributes;
+        Ok(())
     }
 }
 
-impl Element<u32> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
+impl<T: FromTokens> Element<T> {
+    pub fn serialize<'a, I>(token: Tokens<'a>, tokens: &mut TokenStream<I>) -> ElementResult<Self>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
+        match token {
+            Tokens::OpenNode(node) => Element::read(node, tokens),
+            token => Err(FeedError::UnexpectedToken(format!("{token:?}"))),
+        }
+    }
 
-        let mut content = 0u32;
+    // Reads everything up to the end tag of `node`, whose start tag has
+    // already been taken from `tokens`
+    pub fn read<'a, I>(node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<Self>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        let span = tokens.span().cloned();
+        let mut data = T::default();
         let mut attributes = vec![];
         let mut looking_for_attributes = true;
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
+        loop {
+            let Some(token) = tokens.next() else {
+                return Err(FeedError::Unclosed(node.to_string(), span));
+            };
             match token {
                 Tokens::ParameterName(name) => {
                     if looking_for_attributes {
-                        tokens.next();
                         if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
+                            attributes.push((name.into(), value.into_owned()));
                         }
                     }
                 }
-                Tokens::Text(text) => {
+                Tokens::OpenNode(child) => {
                     looking_for_attributes = false;
-                    content = text.parse().unwrap();
+                    // Whatever failed has been read to its end
+                    if let Err(e) = data.child(child, tokens) {
+                        tokens.recover(e)?;
+                    }
                 }
-                Tokens::CloseNode(c_node) => {
+                Tokens::Text(text) | Tokens::CData(text) => {
                     looking_for_attributes = false;
-                    if node == c_node {
-                        break;
+                    if let Err(e) = data.text(&node, &text) {
+                        skip_rest(&node, span.as_ref(), tokens)?;
+                        return Err(e.at(span.as_ref()));
                     }
                 }
+                // Every child was read to its end, so this can only be ours
+                Tokens::CloseNode(_) => break,
                 _ => {
                     looking_for_attributes = false;
                 }
             }
-            tokens.next();
         }
 
-        Self {
-            name: node.clone(),
+        data.finish(&attributes).map_err(|e| e.at(span.as_ref()))?;
+
+        Ok(Self {
+            name: node.into(),
             attributes,
-            data: content,
+            data,
+        })
+    }
+}
+
+// Passes over `node`, whose start tag has already been taken from `tokens`,
+// and everything inside it
+pub(crate) fn skip<'a, I>(node: &Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+where
+    I: Iterator<Item = Spanned<'a>>,
+{
+    let span = tokens.span().cloned();
+    skip_rest(node, span.as_ref(), tokens)
+}
+
+// Passes over what is left of `node`, which starts at `span`, up to its end tag
+fn skip_rest<'a, I>(
+    node: &Name<'a>,
+    span: Option<&Span>,
+    tokens: &mut TokenStream<I>,
+) -> ElementResult<()>
+where
+    I: Iterator<Item = Spanned<'a>>,
+{
+    let mut depth = 0;
+    loop {
+        match tokens.next() {
+            None => return Err(FeedError::Unclosed(node.to_string(), span.cloned())),
+            Some(Tokens::OpenNode(_)) => depth += 1,
+            Some(Tokens::CloseNode(_)) if depth == 0 => return Ok(()),
+            Some(Tokens::CloseNode(_)) => depth -= 1,
+            Some(_) => {}
         }
     }
 }
 
-// RSS
+// Adds to a list that is left as `None` until it has something in it
+pub(crate) fn push<T>(list: &mut Option<Vec<T>>, item: T) {
+    list.get_or_insert_with(Vec::new).push(item);
+}
 
-impl Element<Channel> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
-    where
-        I: std::iter::Iterator<Item = Tokens>,
-    {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
+// A number given in an attribute or as text. Plenty of feeds put something
+// else there, which is as good as leaving it out and not worth dropping the
+// feed over.
+fn number<T: FromStr>(value: &str) -> Option<T> {
+    value.trim().parse().ok()
+}
 
-        assert_eq!(node, "channel".to_string());
+// An RDF attribute such as rdf:about, which some feeds leave unprefixed
+pub(crate) fn rdf_attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(qualified, _)| qualified.rsplit(':').next() == Some(name))
+        .map(|(_, value)| value.as_str())
+}
 
-        let mut attributes = vec![];
+// Whether `node` is an Atom element, whatever prefix it was written with.
+// Feeds missing their xmlns are common enough to allow, as with the root.
+pub(crate) fn is_atom(node: &Name) -> bool {
+    matches!(node.namespace.as_deref(), Some(namespace::ATOM) | None)
+}
 
-        let mut channel = Channel::default();
+// Whether `node` is one of the Dublin Core elements
+pub(crate) fn is_dublin_core(node: &Name) -> bool {
+    node.namespace.as_deref() == Some(namespace::DUBLIN_CORE)
+}
 
-        let mut looking_for_attributes = true;
+// Whether `node` is one of the iTunes or Podcast Index elements
+fn is_podcast(node: &Name) -> bool {
+    matches!(
+        node.namespace.as_deref(),
+        Some(namespace::ITUNES | namespace::PODCAST)
+    )
+}
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(new_node) => {
-                    looking_for_attributes = false;
-                    match new_node.as_str() {
-                        "title" => {
-                            channel.title =
-                                Element::<String>::serialize(tokens.next().unwrap(), tokens);
-                        }
-                        "link" => {
-                            channel.link =
-                                Element::<String>::serialize(tokens.next().unwrap(), tokens);
-                        }
-                        "description" => {
-                            channel.description =
-                                Element::<String>::serialize(tokens.next().unwrap(), tokens);
-                        }
-                        "language" => {
-                            channel.language =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "copyright" => {
-                            channel.copyright =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "managingEditor" => {
-                            channel.managing_editor =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "webMaster" => {
-                            channel.web_master =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "pubDate" => {
-                            channel.pub_date =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "lastBuildDate" => {
-                            channel.last_build_date =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "category" => {
-                            channel.category =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "generator" => {
-                            channel.generator =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "docs" => {
-                            channel.docs =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "cloud" => {
-                            channel.cloud =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "rating" => {
-                            channel.rating =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "item" => {
-                            channel
-                                .items
-                                .push(Element::<Item>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "ttl" => {
-                            channel.ttl =
-                                Some(Element::<u32>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "image" => {
-                            channel.image =
-                                Some(Element::<Image>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        all @ _ => {
-                            //println!("Unimplemented: {all:?}");
-                        }
-                    }
-                }
-                Tokens::CloseNode(close) => {
-                    if close == node {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
-            }
-            tokens.next();
+// Whether `node` is one of the Media RSS elements
+fn is_media(node: &Name) -> bool {
+    node.namespace.as_deref() == Some(namespace::MEDIA)
+}
+
+// Text split up by markup, such as HTML left unescaped in a title, is joined
+// back together
+impl FromTokens for String {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        let name = node.clone();
+        let markup: Element<String> = Element::read(node, tokens)?;
+        if !markup.data.is_empty() {
+            self.text(&name, &markup.data)?;
         }
+        Ok(())
+    }
 
-        Self {
-            name: node,
-            attributes,
-            data: channel,
+    fn text(&mut self, _: &Name, text: &str) -> ElementResult<()> {
+        if !self.is_empty() {
+            self.push(' ');
         }
+        self.push_str(text);
+        Ok(())
     }
 }
 
-impl Element<Item> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
-    where
-        I: std::iter::Iterator<Item = Tokens>,
-    {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
+impl FromTokens for u32 {
+    fn text(&mut self, node: &Name, text: &str) -> ElementResult<()> {
+        *self = text
+            .parse()
+            .map_err(|_| FeedError::InvalidNumber(node.to_string(), text.to_string(), None))?;
+        Ok(())
+    }
+}
 
-        assert_eq!(node, "item".to_string());
+// A date that can't be made sense of is kept for its text rather than failing
+// the feed
+impl FromTokens for Date {
+    fn text(&mut self, _: &Name, text: &str) -> ElementResult<()> {
+        *self = Date::parse(text);
+        Ok(())
+    }
+}
 
-        let mut item = Item::default();
+// Feeds say yes in more ways than one, anything else is no
+impl FromTokens for bool {
+    fn text(&mut self, _: &Name, text: &str) -> ElementResult<()> {
+        *self = matches!(
+            text.to_ascii_lowercase().as_str(),
+            "yes" | "true" | "explicit"
+        );
+        Ok(())
+    }
+}
 
-        let mut attributes = vec![];
+// RSS
 
-        let mut looking_for_attributes = true;
+impl FromTokens for Channel {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.as_str() {
+            "title" => self.title = Element::read(node, tokens)?,
+            "link" => self.link = Element::read(node, tokens)?,
+            "description" => self.description = Element::read(node, tokens)?,
+            "language" => self.language = Some(Element::read(node, tokens)?),
+            "copyright" => self.copyright = Some(Element::read(node, tokens)?),
+            "managingEditor" => self.managing_editor = Some(Element::read(node, tokens)?),
+            "webMaster" => self.web_master = Some(Element::read(node, tokens)?),
+            "pubDate" => self.pub_date = Some(Element::read(node, tokens)?),
+            "lastBuildDate" => self.last_build_date = Some(Element::read(node, tokens)?),
+            "category" => self.category = Some(Element::read(node, tokens)?),
+            "generator" => self.generator = Some(Element::read(node, tokens)?),
+            "docs" => self.docs = Some(Element::read(node, tokens)?),
+            "cloud" => self.cloud = Some(Element::read(node, tokens)?),
+            "rating" => self.rating = Some(Element::read(node, tokens)?),
+            "item" => self.items.push(Element::read(node, tokens)?),
+            "ttl" => self.ttl = Some(Element::read(node, tokens)?),
+            "image" => self.image = Some(Element::read(node, tokens)?),
+            "textInput" => self.text_input = Some(Element::read(node, tokens)?),
+            "skipHours" => self.skip_hours = Some(Element::read(node, tokens)?),
+            "skipDays" => self.skip_days = Some(Element::read(node, tokens)?),
+            "items" => self.sequence = Element::<Sequence>::read(node, tokens)?.data.0,
+            _ if is_podcast(&node) => self
+                .podcast
+                .get_or_insert_with(Default::default)
+                .child(node, tokens)?,
+            _ if is_dublin_core(&node) => self
+                .dublin_core
+                .get_or_insert_with(Default::default)
+                .child(node, tokens)?,
+            _ => skip(&node, tokens)?,
+        }
+        Ok(())
+    }
+}
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(new_node) => {
-                    looking_for_attributes = false;
-                    match new_node.as_str() {
-                        "title" => {
-                            item.title =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "link" => {
-                            item.link =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "description" => {
-                            item.description =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "author" => {
-                            item.author =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "category" => {
-                            item.category =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "enclosure" => {
-                            item.enclosure =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "guid" => {
-                            item.guid =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "pubDate" => {
-                            item.pub_date =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "source" => {
-                            item.source =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "content:encoded" => {
-                            item.content_encoded = Some(Element::<EncodedContent>::serialize(
-                                tokens.next().unwrap(),
-                                tokens,
-                            ))
-                        }
-                        "media" => item.media = None,
-                        _ => {}
-                    }
-                }
-                Tokens::CloseNode(close) => {
-                    if close == node {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
+impl FromTokens for Item {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.as_str() {
+            "title" => self.title = Some(Element::read(node, tokens)?),
+            "link" => self.link = Some(Element::read(node, tokens)?),
+            "description" => self.description = Some(Element::read(node, tokens)?),
+            "author" => self.author = Some(Element::read(node, tokens)?),
+            "category" => self.category = Some(Element::read(node, tokens)?),
+            "enclosure" => push(&mut self.enclosures, Element::read(node, tokens)?),
+            "guid" => self.guid = Some(Element::read(node, tokens)?),
+            "pubDate" => self.pub_date = Some(Element::read(node, tokens)?),
+            "source" => self.source = Some(Element::read(node, tokens)?),
+            _ if node.is(namespace::CONTENT, "encoded") => {
+                self.content_encoded = Some(Element::read(node, tokens)?)
             }
-            tokens.next();
+            _ if is_media(&node) => self
+                .media
+                .get_or_insert_with(Default::default)
+                .child(node, tokens)?,
+            _ if is_podcast(&node) => self
+                .podcast
+                .get_or_insert_with(Default::default)
+                .child(node, tokens)?,
+            _ if is_dublin_core(&node) => self
+                .dublin_core
+                .get_or_insert_with(Default::default)
+                .child(node, tokens)?,
+            _ => skip(&node, tokens)?,
         }
+        Ok(())
+    }
+}
 
-        Self {
-            name: node,
-            attributes,
-            data: item,
+impl FromTokens for Enclosure {
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        for (name, value) in attributes {
+            match name.as_str() {
+                "url" => self.url = value.clone(),
+                "length" => self.length = number(value).filter(|length| *length > 0),
+                "type" => self.mime_type = value.clone(),
+                _ => {}
+            }
         }
+        Ok(())
     }
 }
 
-impl Element<EncodedContent> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
+impl FromTokens for EncodedContent {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
+        self.0.push(Element::read(node, tokens)?);
+        Ok(())
+    }
 
-        assert_eq!(node, "content:encoded".to_string());
+    fn text(&mut self, _: &Name, text: &str) -> ElementResult<()> {
+        self.0.push(Element {
+            name: "p".into(),
+            attributes: vec![],
+            data: text.to_string(),
+        });
+        Ok(())
+    }
+}
 
-        let mut looking_for_attributes = true;
+impl FromTokens for Image {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.as_str() {
+            "url" => self.url = Element::read(node, tokens)?,
+            "title" => self.title = Element::read(node, tokens)?,
+            "link" => self.link = Element::read(node, tokens)?,
+            "description" => self.description = Some(Element::read(node, tokens)?),
+            "width" => self.width = Some(Element::read(node, tokens)?),
+            "height" => self.height = Some(Element::read(node, tokens)?),
+            _ => skip(&node, tokens)?,
+        }
+        Ok(())
+    }
+}
 
-        let mut attributes = vec![];
-        let mut elements = vec![];
-        let mut content = String::new();
+impl FromTokens for Rdf {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.as_str() {
+            "channel" => self.channel = Some(Element::read(node, tokens)?),
+            "image" => self.image = Some(Element::read(node, tokens)?),
+            "textinput" | "textInput" => self.text_input = Some(Element::read(node, tokens)?),
+            "item" => self.items.push(Element::read(node, tokens)?),
+            _ => skip(&node, tokens)?,
+        }
+        Ok(())
+    }
+}
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(_) => {
-                    looking_for_attributes = false;
-                    elements.push(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                }
-                Tokens::Text(text) => {
-                    elements.push(Element {
-                        name: "p".into(),
-                        attributes: vec![],
-                        data: text,
-                    });
-                }
-                Tokens::CloseNode(close) => {
-                    looking_for_attributes = false;
-                    if node == close {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
+// The <rdf:li> of the <rdf:Seq> inside <items>
+impl FromTokens for Sequence {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.local_name() {
+            "Seq" => self
+                .0
+                .extend(Element::<Sequence>::read(node, tokens)?.data.0),
+            "li" => {
+                let li: Element<String> = Element::read(node, tokens)?;
+                if let Some(resource) = rdf_attribute(&li.attributes, "resource") {
+                    self.0.push(resource.to_string());
                 }
             }
-            tokens.next();
+            _ => skip(&node, tokens)?,
         }
+        Ok(())
+    }
+}
 
-        Self {
-            name: node,
-            attributes,
-            data: EncodedContent(elements, content),
+impl FromTokens for Cloud {
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        for (name, value) in attributes {
+            match name.as_str() {
+                "domain" => self.domain = value.clone(),
+                "port" => self.port = number(value),
+                "path" => self.path = value.clone(),
+                "registerProcedure" => self.register_procedure = value.clone(),
+                "protocol" => self.protocol = value.clone(),
+                _ => {}
+            }
         }
+        Ok(())
     }
 }
 
-impl Element<Image> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
+impl FromTokens for TextInput {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
-
-        assert_eq!(node, "image".to_string());
-
-        let mut looking_for_attributes = true;
-
-        let mut attributes = vec![];
-
-        let mut image = Image::default();
+        match node.as_str() {
+            "title" => self.title = Element::read(node, tokens)?,
+            "description" => self.description = Element::read(node, tokens)?,
+            "name" => self.name = Element::read(node, tokens)?,
+            "link" => self.link = Element::read(node, tokens)?,
+            _ => skip(&node, tokens)?,
+        }
+        Ok(())
+    }
+}
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(new_node) => match new_node.as_str() {
-                    "url" => {
-                        image.url = Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                    }
-                    "title" => {
-                        image.title = Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                    }
-                    "link" => {
-                        image.link = Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                    }
-                    "description" => {
-                        image.description =
-                            Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                    }
-                    "width" => {
-                        image.width =
-                            Some(Element::<u32>::serialize(tokens.next().unwrap(), tokens))
-                    }
-                    "height" => {
-                        image.height =
-                            Some(Element::<u32>::serialize(tokens.next().unwrap(), tokens))
-                    }
-                    other @ _ => {
-                        //println!("Unexpected/Unimplemented Image Element: {other:?}")
-                    }
-                },
-                Tokens::CloseNode(close) => {
-                    looking_for_attributes = false;
-                    if node == close {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
+impl FromTokens for SkipHours {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        if node.as_str() == "hour" {
+            let hour: Element<String> = Element::read(node, tokens)?;
+            match number::<u8>(&hour.data) {
+                Some(24) => self.hours.push(0),
+                Some(hour) if hour < 24 => self.hours.push(hour),
+                _ => {}
             }
-            tokens.next();
+            Ok(())
+        } else {
+            skip(&node, tokens)
         }
+    }
+}
 
-        Self {
-            name: node,
-            attributes,
-            data: image,
+impl FromTokens for SkipDays {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        if node.as_str() == "day" {
+            let day: Element<String> = Element::read(node, tokens)?;
+            self.days.extend(Days::parse(&day.data));
+            Ok(())
+        } else {
+            skip(&node, tokens)
         }
     }
 }
 
 // ATOM
-/* TEMPLATE
-impl Element<atom::<INSERT>> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
+
+impl FromTokens for atom::Person {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
-
-        let mut attributes = vec![];
-
-        let mut <INSERT> = atom::<INSERT>::default();
+        match node.local_name() {
+            _ if !is_atom(&node) => skip(&node, tokens)?,
+            "name" => self.name = Element::read(node, tokens)?,
+            "uri" => self.uri = Some(Element::read(node, tokens)?),
+            "email" => self.email = Some(Element::read(node, tokens)?),
+            _ => skip(&node, tokens)?,
+        }
+        Ok(())
+    }
+}
 
-        let mut looking_for_attributes = true;
+impl FromTokens for atom::Category {}
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(new_node) => {
-                    looking_for_attributes = false;
-                    match new_node.as_str() {
-                        all @ _ => {
-                            println!("Unimplemented: {all:?}");
-                        }
-                    }
-                }
-                Tokens::CloseNode(close) => {
-                    if close == node {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
-            }
-            tokens.next();
-        }
+impl FromTokens for atom::Link {
+    fn text(&mut self, _: &Name, text: &str) -> ElementResult<()> {
+        self.0 = Some(text.to_string());
+        Ok(())
+    }
+}
 
-        Self {
-            name: node,
-            attributes,
-            data: <INSERT>,
+impl FromTokens for atom::Entry {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.local_name() {
+            _ if is_media(&node) => self
+                .media
+                .get_or_insert_with(Default::default)
+                .child(node, tokens)?,
+            _ if is_dublin_core(&node) => self
+                .dublin_core
+                .get_or_insert_with(Default::default)
+                .child(node, tokens)?,
+            _ if !is_atom(&node) => skip(&node, tokens)?,
+            "author" => push(&mut self.authors, Element::read(node, tokens)?),
+            "category" => push(&mut self.categories, Element::read(node, tokens)?),
+            "content" => self.content = Element::read(node, tokens)?,
+            "contributor" => push(&mut self.contributors, Element::read(node, tokens)?),
+            "id" => self.id = Element::read(node, tokens)?,
+            "link" => push(&mut self.links, Element::read(node, tokens)?),
+            "published" => self.published = Some(Element::read(node, tokens)?),
+            "rights" => self.rights = Some(Element::read(node, tokens)?),
+            "summary" => self.summary = Some(Element::read(node, tokens)?),
+            "title" => self.title = Element::read(node, tokens)?,
+            "updated" => self.updated = Element::read(node, tokens)?,
+            _ => skip(&node, tokens)?,
         }
+        Ok(())
     }
 }
-*/
 
-impl Element<atom::Person> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
+impl FromTokens for Option<atom::Entry> {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
-
-        let mut attributes = vec![];
+        self.get_or_insert_with(Default::default)
+            .child(node, tokens)
+    }
 
-        let mut person = atom::Person::default();
+    // An entry with attributes but no title is left out
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        let entry = self.take().unwrap_or_default();
+        if attributes.is_empty() || !entry.title.data.is_empty() {
+            *self = Some(entry);
+        }
+        Ok(())
+    }
+}
 
-        let mut looking_for_attributes = true;
+// Media RSS
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(new_node) => {
-                    looking_for_attributes = false;
-                    match new_node.as_str() {
-                        "name" => {
-                            person.name =
-                                Element::<String>::serialize(tokens.next().unwrap(), tokens);
-                        }
-                        "uri" => {
-                            person.uri =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        "email" => {
-                            person.email =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens));
-                        }
-                        all @ _ => {
-                            //println!("Unimplemented: {all:?}");
-                        }
-                    }
-                }
-                Tokens::CloseNode(close) => {
-                    if close == node {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
-            }
-            tokens.next();
+impl FromTokens for Media {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.local_name() {
+            "content" if is_media(&node) => push(&mut self.contents, Element::read(node, tokens)?),
+            "group" if is_media(&node) => push(&mut self.groups, Element::read(node, tokens)?),
+            _ => self.details.child(node, tokens)?,
         }
+        Ok(())
+    }
+}
 
-        Self {
-            name: node,
-            attributes,
-            data: person,
+impl FromTokens for Group {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.local_name() {
+            "content" if is_media(&node) => push(&mut self.contents, Element::read(node, tokens)?),
+            _ => self.details.child(node, tokens)?,
         }
+        Ok(())
     }
 }
 
-impl Element<atom::Category> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
+impl FromTokens for Content {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
-
-        let mut attributes = vec![];
-
-        let mut category = atom::Category::default();
-
-        let mut looking_for_attributes = true;
+        self.details.child(node, tokens)
+    }
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(new_node) => {
-                    looking_for_attributes = false;
-                    match new_node.as_str() {
-                        all @ _ => {
-                            //println!("Unimplemented: {all:?}");
-                        }
-                    }
-                }
-                Tokens::CloseNode(close) => {
-                    if close == node {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        for (name, value) in attributes {
+            match name.as_str() {
+                "url" => self.url = Some(value.clone()),
+                "type" => self.mime_type = Some(value.clone()),
+                "medium" => self.medium = Some(value.clone()),
+                "duration" => self.duration = number(value),
+                "width" => self.width = number(value),
+                "height" => self.height = number(value),
+                _ => {}
             }
-            tokens.next();
-        }
-
-        Self {
-            name: node,
-            attributes,
-            data: category,
         }
+        Ok(())
     }
 }
 
-impl Element<atom::Link> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
+impl FromTokens for Details {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
-
-        let mut attributes = vec![];
-
-        let mut link = atom::Link::default();
-
-        let mut looking_for_attributes = true;
+        if !is_media(&node) {
+            return skip(&node, tokens);
+        }
+        match node.local_name() {
+            "title" => self.title = Some(Element::read(node, tokens)?),
+            "description" => self.description = Some(Element::read(node, tokens)?),
+            "thumbnail" => push(&mut self.thumbnails, Element::read(node, tokens)?),
+            "credit" => push(&mut self.credits, Element::read(node, tokens)?),
+            "player" => self.player = Some(Element::read(node, tokens)?),
+            _ => skip(&node, tokens)?,
+        }
+        Ok(())
+    }
+}
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(new_node) => {
-                    looking_for_attributes = false;
-                    match new_node.as_str() {
-                        all @ _ => {
-                            //println!("Unimplemented: {all:?}");
-                        }
-                    }
-                }
-                Tokens::Text(text) => {
-                    looking_for_attributes = false;
-                    link.0 = Some(text);
-                }
-                Tokens::CloseNode(close) => {
-                    if close == node {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
+impl FromTokens for Thumbnail {
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        for (name, value) in attributes {
+            match name.as_str() {
+                "url" => self.url = value.clone(),
+                "width" => self.width = number(value),
+                "height" => self.height = number(value),
+                "time" => self.time = Some(value.clone()),
+                _ => {}
             }
-            tokens.next();
         }
+        Ok(())
+    }
+}
 
-        Self {
-            name: node,
-            attributes,
-            data: link,
+impl FromTokens for Player {
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        for (name, value) in attributes {
+            match name.as_str() {
+                "url" => self.url = value.clone(),
+                "width" => self.width = number(value),
+                "height" => self.height = number(value),
+                _ => {}
+            }
         }
+        Ok(())
     }
 }
 
-impl Element<Option<atom::Entry>> {
-    pub fn serialize<I>(token: Tokens, tokens: &mut Peekable<I>) -> Self
+// Dublin Core
+
+impl FromTokens for DublinCore {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
     where
-        I: std::iter::Iterator<Item = Tokens>,
+        I: Iterator<Item = Spanned<'a>>,
     {
-        let Tokens::OpenNode(node) = token else {
-            todo!("Implement Error Handling of Incorrect Node: {token:?}");
-        };
-
-        let mut attributes = vec![];
-
-        let mut entry = atom::Entry::default();
+        match node.local_name() {
+            "title" => self.title = Some(Element::read(node, tokens)?),
+            "creator" => push(&mut self.creators, Element::read(node, tokens)?),
+            "subject" => push(&mut self.subjects, Element::read(node, tokens)?),
+            "description" => self.description = Some(Element::read(node, tokens)?),
+            "publisher" => self.publisher = Some(Element::read(node, tokens)?),
+            "contributor" => push(&mut self.contributors, Element::read(node, tokens)?),
+            "date" => self.date = Some(Element::read(node, tokens)?),
+            "identifier" => self.identifier = Some(Element::read(node, tokens)?),
+            "language" => self.language = Some(Element::read(node, tokens)?),
+            "rights" => self.rights = Some(Element::read(node, tokens)?),
+            _ => skip(&node, tokens)?,
+        }
+        Ok(())
+    }
+}
 
-        let mut looking_for_attributes = true;
+// Podcasts
 
-        let mut authors = vec![];
-        let mut categories = vec![];
-        let mut contributors = vec![];
-        let mut links = vec![];
+impl FromTokens for Show {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.local_name() {
+            "author" => self.author = Some(Element::read(node, tokens)?),
+            "image" => self.image = Some(Element::read(node, tokens)?),
+            "explicit" => self.explicit = Some(Element::read(node, tokens)?),
+            "summary" => self.summary = Some(Element::read(node, tokens)?),
+            _ => skip(&node, tokens)?,
+        }
+        Ok(())
+    }
+}
 
-        while let Some(token) = tokens.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if looking_for_attributes {
-                        tokens.next();
-                        if let Some(Tokens::ParameterValue(value)) = tokens.next() {
-                            attributes.push((name, value));
-                            continue;
-                        }
-                    } else {
-                        tokens.next();
-                    }
-                }
-                Tokens::OpenNode(new_node) => {
-                    looking_for_attributes = false;
-                    match new_node.as_str() {
-                        "author" => authors.push(Element::<atom::Person>::serialize(
-                            tokens.next().unwrap(),
-                            tokens,
-                        )),
-                        "category" => categories.push(Element::<atom::Category>::serialize(
-                            tokens.next().unwrap(),
-                            tokens,
-                        )),
-                        "content" => {
-                            entry.content =
-                                Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                        }
-                        "contributor" => contributors.push(Element::<atom::Person>::serialize(
-                            tokens.next().unwrap(),
-                            tokens,
-                        )),
-                        "id" => {
-                            entry.id = Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                        }
-                        "link" => links.push(Element::<atom::Link>::serialize(
-                            tokens.next().unwrap(),
-                            tokens,
-                        )),
-                        "published" => {
-                            entry.published =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "rights" => {
-                            entry.rights =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "summary" => {
-                            entry.summary =
-                                Some(Element::<String>::serialize(tokens.next().unwrap(), tokens))
-                        }
-                        "title" => {
-                            entry.title =
-                                Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                        }
-                        "updated" => {
-                            entry.updated =
-                                Element::<String>::serialize(tokens.next().unwrap(), tokens)
-                        }
-                        all @ _ => {
-                            //println!("Unimplemented: {all:?}");
-                        }
-                    }
-                }
-                Tokens::CloseNode(close) => {
-                    if close == node {
-                        break;
-                    }
-                }
-                _ => {
-                    looking_for_attributes = false;
-                }
+impl FromTokens for Episode {
+    fn child<'a, I>(&mut self, node: Name<'a>, tokens: &mut TokenStream<I>) -> ElementResult<()>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        match node.local_name() {
+            "author" => self.author = Some(Element::read(node, tokens)?),
+            "duration" => {
+                let duration: Element<String> = Element::read(node, tokens)?;
+                self.duration = Duration::parse(&duration.data);
             }
-            tokens.next();
+            "episode" => {
+                let episode: Element<String> = Element::read(node, tokens)?;
+                self.episode = number(&episode.data);
+            }
+            "season" => {
+                let season: Element<String> = Element::read(node, tokens)?;
+                self.season = number(&season.data);
+            }
+            "image" => self.image = Some(Element::read(node, tokens)?),
+            "explicit" => self.explicit = Some(Element::read(node, tokens)?),
+            "summary" => self.summary = Some(Element::read(node, tokens)?),
+            "transcript" => push(&mut self.transcripts, Element::read(node, tokens)?),
+            "chapters" => self.chapters = Some(Element::read(node, tokens)?),
+            _ => skip(&node, tokens)?,
         }
+        Ok(())
+    }
+}
 
-        entry.authors = if authors.is_empty() {
-            None
-        } else {
-            Some(authors)
-        };
-
-        entry.contributors = if contributors.is_empty() {
-            None
-        } else {
-            Some(contributors)
-        };
-
-        entry.categories = if categories.is_empty() {
-            None
-        } else {
-            Some(categories)
-        };
-
-        entry.links = if links.is_empty() { None } else { Some(links) };
+impl FromTokens for PodcastImage {
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        for (name, value) in attributes {
+            if name == "href" {
+                self.href = value.clone();
+            }
+        }
+        Ok(())
+    }
+}
 
-        let data = if attributes.len() > 0 && entry.title.data.is_empty() {
-            None
-        } else {
-            Some(entry)
-        };
+impl FromTokens for Transcript {
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        for (name, value) in attributes {
+            match name.as_str() {
+                "url" => self.url = value.clone(),
+                "type" => self.mime_type = value.clone(),
+                "language" => self.language = Some(value.clone()),
+                "rel" => self.rel = Some(value.clone()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
 
-        Self {
-            name: node,
-            attributes,
-            data,
+impl FromTokens for Chapters {
+    fn finish(&mut self, attributes: &[(String, String)]) -> ElementResult<()> {
+        for (name, value) in attributes {
+            match name.as_str() {
+                "url" => self.url = value.clone(),
+                "type" => self.mime_type = value.clone(),
+                _ => {}
+            }
         }
+        Ok(())
     }
 }
@@ -0,0 +1,97 @@
+use greyxml::Span;
+use std::fmt;
+
+// Errors found in the elements of a feed end with the span of the start tag
+// they're about, when it's known
+#[derive(Debug)]
+pub enum FeedError {
+    // The document could not be read as XML
+    Xml(greyxml::Error),
+    // An element the feed cannot do without, such as `<rss>` or `<channel>`
+    Missing(String, Option<Span>),
+    // The tokens ran out while the named element was still open
+    Unclosed(String, Option<Span>),
+    // The element that was expected and the one found in its place
+    UnexpectedElement(String, String, Option<Span>),
+    // What was found where the start of an element was expected
+    UnexpectedToken(String),
+    // The element or attribute that should hold a number, and what it held
+    InvalidNumber(String, String, Option<Span>),
+    // The document could not be read as JSON, or not as a JSON Feed
+    Json(serde_json::Error),
+    // The version a JSON Feed gave, which isn't one that can be read
+    UnsupportedVersion(String),
+    // The URL of a later page of a feed, and why it couldn't be read
+    NextPage(String, String),
+    // The document is not RSS, Atom or JSON Feed, as far as could be told
+    UnrecognisedFormat,
+}
+
+impl FeedError {
+    // Where in the document the error is, for those that know
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            FeedError::Missing(_, span)
+            | FeedError::Unclosed(_, span)
+            | FeedError::UnexpectedElement(_, _, span)
+            | FeedError::InvalidNumber(_, _, span) => span.as_ref(),
+            _ => None,
+        }
+    }
+
+    // Points at `at` if it doesn't point anywhere yet
+    pub(crate) fn at(mut self, at: Option<&Span>) -> FeedError {
+        if let FeedError::Missing(_, span)
+        | FeedError::Unclosed(_, span)
+        | FeedError::UnexpectedElement(_, _, span)
+        | FeedError::InvalidNumber(_, _, span) = &mut self
+        {
+            if span.is_none() {
+                *span = at.cloned();
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for FeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(span) = self.span() {
+            write!(f, "{span}: ")?;
+        }
+        match self {
+            FeedError::Xml(e) => write!(f, "{e}"),
+            FeedError::Missing(name, _) => write!(f, "the feed has no <{name}>"),
+            FeedError::Unclosed(name, _) => write!(f, "<{name}> was never closed"),
+            FeedError::UnexpectedElement(expected, found, _) => {
+                write!(f, "expected <{expected}> but found <{found}>")
+            }
+            FeedError::UnexpectedToken(found) => {
+                write!(f, "expected the start of an element but found {found}")
+            }
+            FeedError::InvalidNumber(name, text, _) => {
+                write!(f, "{name} should be a number but was {text:?}")
+            }
+            FeedError::Json(e) => write!(f, "{e}"),
+            FeedError::UnsupportedVersion(version) => {
+                write!(f, "{version:?} is not a JSON Feed version")
+            }
+            FeedError::NextPage(url, e) => write!(f, "the next page at {url} failed: {e}"),
+            FeedError::UnrecognisedFormat => write!(f, "unrecognised feed format"),
+        }
+    }
+}
+
+impl std::error::Error for FeedError {}
+
+impl From<greyxml::Error> for FeedError {
+    fn from(e: greyxml::Error) -> Self {
+        FeedError::Xml(e)
+    }
+}
+
+impl From<serde_json::Error> for FeedError {
+    fn from(e: serde_json::Error) -> Self {
+        FeedError::Json(e)
+    }
+}
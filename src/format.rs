@@ -0,0 +1,89 @@
+use crate::error::FeedError;
+
+// The kinds of feed there is a parser for. RSS 0.90 and 1.0 are RDF documents
+// but are read by the same parser as the rest of RSS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Rss,
+    Atom,
+    Json,
+}
+
+// Which format a feed is in, going by `start`, the first part of the document,
+// and the Content-Type it was served with if there was one. What the document
+// is wins out, as servers send feeds as text/xml, text/html or anything else
+// often enough, so a root element that isn't a feed's is an error whatever
+// the Content-Type says. The Content-Type is only fallen back on when there
+// is no root element in `start` at all, such as when it is further in.
+pub fn detect(start: &[u8], content_type: Option<&str>) -> Result<Format, FeedError> {
+    let start = start.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(start);
+    let start = start.trim_ascii_start();
+    if start.starts_with(b"{") {
+        return Ok(Format::Json);
+    }
+    match root_element(start) {
+        Some(root) => root_format(root),
+        None => content_type.and_then(media_type),
+    }
+    .ok_or(FeedError::UnrecognisedFormat)
+}
+
+fn root_format(root: &[u8]) -> Option<Format> {
+    // Whatever prefix it was written with
+    let local_name = match root.iter().position(|&byte| byte == b':') {
+        Some(colon) => &root[colon + 1..],
+        None => root,
+    };
+    match local_name {
+        b"rss" | b"RDF" => Some(Format::Rss),
+        b"feed" => Some(Format::Atom),
+        _ => None,
+    }
+}
+
+// The name of the first element, after any XML declaration, processing
+// instructions, comments and doctype
+fn root_element(mut rest: &[u8]) -> Option<&[u8]> {
+    loop {
+        rest = rest.trim_ascii_start();
+        if let Some(instruction) = rest.strip_prefix(b"<?") {
+            rest = after(instruction, b"?>")?;
+        } else if let Some(comment) = rest.strip_prefix(b"<!--") {
+            rest = after(comment, b"-->")?;
+        } else if let Some(doctype) = rest.strip_prefix(b"<!") {
+            // An internal subset has a `>` of its own in it
+            let end = doctype
+                .iter()
+                .position(|&byte| byte == b'[' || byte == b'>')?;
+            rest = if doctype[end] == b'[' {
+                after(after(&doctype[end..], b"]")?, b">")?
+            } else {
+                &doctype[end + 1..]
+            };
+        } else {
+            let element = rest.strip_prefix(b"<")?;
+            let length = element
+                .iter()
+                .position(|&byte| byte.is_ascii_whitespace() || byte == b'>' || byte == b'/')?;
+            return Some(&element[..length]).filter(|name| !name.is_empty());
+        }
+    }
+}
+
+// What comes after the first `end` in `text`
+fn after<'a>(text: &'a [u8], end: &[u8]) -> Option<&'a [u8]> {
+    let position = text.windows(end.len()).position(|window| window == end)?;
+    Some(&text[position + end.len()..])
+}
+
+// The format a Content-Type names, ignoring the generic XML and text types
+// that could be anything
+fn media_type(content_type: &str) -> Option<Format> {
+    let media_type = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    match media_type.as_str() {
+        "application/rss+xml" | "application/rdf+xml" => Some(Format::Rss),
+        "application/atom+xml" => Some(Format::Atom),
+        "application/feed+json" | "application/json" => Some(Format::Json),
+        _ => None,
+    }
+}
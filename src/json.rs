@@ -0,0 +1,179 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::date::Date;
+use crate::error::FeedError;
+
+type JsonResult<T> = Result<T, FeedError>;
+
+// https://www.jsonfeed.org/version/1.1/
+// Version 1.0 feeds are read the same way, their one `author` is moved into
+// `authors` as 1.1 would have it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Feed {
+    // The URL of the version of the spec, such as https://jsonfeed.org/version/1.1
+    pub version: String,
+    pub title: String,
+    pub home_page_url: Option<String>,
+    pub feed_url: Option<String>,
+    pub description: Option<String>,
+    pub user_comment: Option<String>,
+    // Where the next page of items is, for feeds that don't give them all at once
+    pub next_url: Option<String>,
+    pub icon: Option<String>,
+    pub favicon: Option<String>,
+    pub authors: Vec<Author>,
+    author: Option<Author>,
+    pub language: Option<String>,
+    // Set when the feed will never be updated again
+    pub expired: bool,
+    pub items: Vec<Item>,
+    // Whatever a publisher added of their own, under keys starting with `_`
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+// The most pages read after the first one, however many more a feed links to
+pub const MAX_PAGES: usize = 10;
+
+// The versions there are, as feeds name them. Some give http rather than https
+// or add a trailing slash, which is no reason to turn them away.
+const VERSIONS: [&str; 4] = [
+    "https://jsonfeed.org/version/1",
+    "https://jsonfeed.org/version/1.1",
+    "http://jsonfeed.org/version/1",
+    "http://jsonfeed.org/version/1.1",
+];
+
+impl Feed {
+    pub fn serialize<R: Read>(reader: R) -> JsonResult<Feed> {
+        let mut feed: Feed = serde_json::from_reader(reader)?;
+        if !VERSIONS.contains(&feed.version.trim_end_matches('/')) {
+            return Err(FeedError::UnsupportedVersion(feed.version));
+        }
+        feed.authors.extend(feed.author.take());
+        feed.extensions.retain(|key, _| key.starts_with('_'));
+        for item in &mut feed.items {
+            item.authors.extend(item.author.take());
+            item.extensions.retain(|key, _| key.starts_with('_'));
+        }
+        Ok(feed)
+    }
+
+    // Reads the pages that follow this one, which was read from `url`, adding
+    // their items after its own. `fetch` reads the page at a URL. It stops at
+    // the last page, after MAX_PAGES or at a URL it has already been to. A
+    // page that can't be read stops it too, keeping what was read before it.
+    // The page it stopped short of is left in `next_url`, unless it was one
+    // already read.
+    pub fn follow_pages<E>(
+        &mut self,
+        url: &str,
+        mut fetch: impl FnMut(&str) -> Result<Feed, E>,
+    ) -> Result<(), E> {
+        let mut seen: HashSet<String> = self.feed_url.iter().cloned().collect();
+        seen.insert(url.to_string());
+        for _ in 0..MAX_PAGES {
+            let Some(next) = self.next_url.take() else {
+                return Ok(());
+            };
+            if !seen.insert(next.clone()) {
+                return Ok(());
+            }
+            match fetch(&next) {
+                Ok(page) => {
+                    self.items.extend(page.items);
+                    self.next_url = page.next_url;
+                }
+                Err(e) => {
+                    self.next_url = Some(next);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Item {
+    // Meant to be a string, but plenty of feeds give a number
+    #[serde(deserialize_with = "id")]
+    pub id: String,
+    pub url: Option<String>,
+    // What the item is about, when it is somewhere else
+    pub external_url: Option<String>,
+    pub title: Option<String>,
+    // At least one of these is given, or both
+    pub content_html: Option<String>,
+    pub content_text: Option<String>,
+    pub summary: Option<String>,
+    pub image: Option<String>,
+    pub banner_image: Option<String>,
+    #[serde(deserialize_with = "date")]
+    pub date_published: Option<Date>,
+    #[serde(deserialize_with = "date")]
+    pub date_modified: Option<Date>,
+    pub authors: Vec<Author>,
+    author: Option<Author>,
+    pub tags: Vec<String>,
+    pub language: Option<String>,
+    pub attachments: Vec<Attachment>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl Item {
+    // The first of the item's authors with a name
+    pub fn author(&self) -> Option<&str> {
+        self.authors
+            .iter()
+            .find_map(|author| author.name.as_deref())
+    }
+
+    // When the item was published, or else last modified
+    pub fn date(&self) -> Option<&Date> {
+        self.date_published.as_ref().or(self.date_modified.as_ref())
+    }
+}
+
+// Any of these can be left out, but not all of them
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Author {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+}
+
+// A file that goes with an item, like an RSS enclosure
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Attachment {
+    pub url: String,
+    pub mime_type: String,
+    // Set when there are several attachments of the same thing in different
+    // formats, which all share the title
+    pub title: Option<String>,
+    pub size_in_bytes: Option<u64>,
+    pub duration_in_seconds: Option<f64>,
+}
+
+fn id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(id) => id,
+        Value::Null => String::new(),
+        id => id.to_string(),
+    })
+}
+
+// RFC 3339 dates, though they are read as leniently as RSS and Atom ones
+fn date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Date>, D::Error> {
+    let text: Option<String> = Option::deserialize(deserializer)?;
+    Ok(text.map(|text| Date::parse(&text)))
+}
@@ -0,0 +1,13 @@
+// The feed parsers, split out of the binary so benches and fuzz targets can
+// drive them directly
+pub mod atom;
+pub mod date;
+pub mod dublin_core;
+pub mod elements;
+pub mod error;
+pub mod format;
+pub mod json;
+pub mod media;
+pub mod namespace;
+pub mod podcast;
+pub mod rss;
@@ -1,27 +1,25 @@
-#![feature(let_chains)]
-
 use std::env;
-use std::io::{self, Read, Stdout, Write};
+use std::io::{self, BufRead, BufReader, Cursor, Read, Stdout};
 use std::path::PathBuf;
-use std::sync::mpsc::{channel, Receiver, Sender};
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use std::{error::Error, fs::File};
 
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers, ModifierKeyCode};
+use chrono::{DateTime, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
-use greyxml::{lex, tokenize};
+use greyxml::{lex, tokenize_html, DecodingReader};
+use kisserss::date::Date;
+use kisserss::error::FeedError;
+use kisserss::format::{self, Format};
+use kisserss::media::Media;
+use kisserss::{atom, json, rss};
 use ratatui::prelude::*;
 use ratatui::widgets::*;
 use reqwest::blocking::get;
-
-mod atom;
-mod elements;
-mod rss;
+use reqwest::header::CONTENT_TYPE;
 
 #[derive(Clone)]
 struct StatefulList<T> {
@@ -35,7 +33,7 @@ struct StatefulList<T> {
 impl<T> StatefulList<T> {
     fn with_items(items: Vec<T>, is_active: bool) -> StatefulList<T> {
         let mut state = ListState::default();
-        if items.len() > 0 {
+        if !items.is_empty() {
             state.select(Some(0));
         }
         StatefulList {
@@ -72,40 +70,16 @@ impl<T> StatefulList<T> {
         };
         self.state.select(Some(i));
     }
-
-    fn unselect(&mut self) { self.state.select(None); }
-}
-
-impl<String: std::fmt::Display> StatefulList<String> {
-    fn to_list(&self) -> List {
-        let list_items: Vec<ListItem> = self
-            .items
-            .iter()
-            .enumerate()
-            .map(|(index, item)| {
-                if let Some(i) = self.state.selected() && i == index {
-                    if self.active {
-                        ListItem::new(format!("**{item}**")).italic()
-                    } else {
-                        ListItem::new(format!("**{item}**"))
-                    }
-                } else {
-                    ListItem::new(item.to_string())
-                }
-            })
-            .collect();
-        List::new(list_items)
-    }
 }
 
 impl StatefulList<(String, usize)> {
-    fn to_list_tuple(&self) -> List {
+    fn to_list_tuple(&self) -> List<'_> {
         let list_items: Vec<ListItem> = self
             .items
             .iter()
             .enumerate()
             .map(|(index, item)| {
-                if let Some(i) = self.state.selected() && i == index {
+                if self.state.selected() == Some(index) {
                     if self.active {
                         ListItem::new(format!("**{}**", item.0)).italic()
                     } else {
@@ -122,37 +96,58 @@ impl StatefulList<(String, usize)> {
 
 #[derive(Debug)]
 enum FeedType {
-    Rss(rss::Feed),
-    Atom(atom::Feed),
+    Rss(Box<rss::Feed>),
+    Atom(Box<atom::Feed>),
+    Json(Box<json::Feed>),
+}
+
+impl FeedType {
+    fn title(&self) -> String {
+        match self {
+            FeedType::Rss(rss) => rss.channel.data.title.data.clone(),
+            FeedType::Atom(atom) => atom.contents.title.data.clone(),
+            FeedType::Json(json) => json.title.clone(),
+        }
+    }
+
+    // Whether a scheduled refresh should read the feed again at `now`, only
+    // RSS says how often that should be
+    fn due(&self, fetched: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        match self {
+            FeedType::Rss(rss) => rss.channel.data.due(fetched, now),
+            _ => now - fetched >= chrono::Duration::minutes(rss::DEFAULT_TTL.into()),
+        }
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut input = String::new();
     let mut args = env::args();
     args.next();
 
     let mut feeds = vec![];
+    let mut warnings = vec![];
+    let mut sources = vec![];
 
     // TODO: Multithreaded loading?
     for _ in 0..args.len() {
         let Some(path_string) = args.next() else {
             panic!("No path given");
         };
-        if path_string.starts_with("http://") || path_string.starts_with("https://") {
-            // FIXME: Handle Errors
-            input = get_web_feed(&path_string)?;
-        } else {
-            let path = PathBuf::from(path_string.clone());
-            let mut file = File::open(path)?;
-            file.read_to_string(&mut input)?;
-        }
-        if path_string.ends_with(".atom") {
-            feeds.push(FeedType::Atom(atom::Feed::serialize(&input)?));
-        } else {
-            feeds.push(FeedType::Rss(rss::Feed::serialize(&input)?));
+        match load_feed(&path_string) {
+            Ok((feed, feed_warnings)) => {
+                feeds.push(feed);
+                warnings.push(feed_warnings);
+                sources.push(path_string);
+            }
+            // One broken feed shouldn't stop the rest from being read
+            Err(e) => eprintln!("{path_string}: {e}"),
         }
     }
 
+    if feeds.is_empty() {
+        return Err("No feed could be read".into());
+    }
+
     /*
     let lexed = lex(input.into())?;
     let tokens = tokenize(&mut lexed.into_iter())?;
@@ -164,7 +159,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     //dbg!(feed);
 
     let mut terminal = setup_terminal()?;
-    let mut app = App::new(feeds, &mut terminal);
+    let mut app = App::new(feeds, warnings, sources, &mut terminal);
     app.run()?;
 
     restore_terminal(&mut terminal)?;
@@ -172,9 +167,102 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn get_web_feed(source: &str) -> Result<String, Box<dyn Error>> {
-    let body = get(source)?.text()?;
-    Ok(body)
+// Reads the feed at `source`, a URL or the path of a file, along with any
+// later pages of it a JSON Feed links to
+fn load_feed(source: &str) -> Result<(FeedType, Vec<FeedError>), Box<dyn Error>> {
+    let (input, content_type) = if is_web(source) {
+        get_web_feed(source)?
+    } else {
+        (feed_reader(File::open(PathBuf::from(source))?, None)?, None)
+    };
+    let (mut feed, mut warnings) = serialize_feed(content_type.as_deref(), input)?;
+    if let FeedType::Json(ref mut json) = feed {
+        // The items of the pages that could be read are still worth showing
+        if let Err(e) = json.follow_pages(source, get_json_page) {
+            let url = json.next_url.clone().unwrap_or_default();
+            warnings.push(FeedError::NextPage(url, e.to_string()));
+        }
+    }
+    Ok((feed, warnings))
+}
+
+fn is_web(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+// A later page of a JSON Feed, which is only ever fetched from the web
+fn get_json_page(url: &str) -> Result<json::Feed, Box<dyn Error>> {
+    if !is_web(url) {
+        return Err("not a web address".into());
+    }
+    let (input, _) = get_web_feed(url)?;
+    Ok(json::Feed::serialize(input)?)
+}
+
+// How much of a feed is looked at to tell what format it is in, enough to get
+// past an XML declaration, a stylesheet and a comment or two
+const SNIFF_LENGTH: u64 = 4096;
+
+// Feeds are read leniently, what was wrong with them is shown alongside them.
+// Which parser reads a feed is down to what it looks like, along with the
+// Content-Type it was served with if it came from the web.
+fn serialize_feed<R: BufRead>(
+    content_type: Option<&str>,
+    mut input: R,
+) -> Result<(FeedType, Vec<FeedError>), Box<dyn Error>> {
+    let mut start = vec![];
+    input.by_ref().take(SNIFF_LENGTH).read_to_end(&mut start)?;
+    let format = format::detect(&start, content_type)?;
+    let input = Cursor::new(start).chain(input);
+    match format {
+        Format::Rss => {
+            let (feed, warnings) = rss::Feed::serialize_lenient(input)?;
+            Ok((FeedType::Rss(Box::new(feed)), warnings))
+        }
+        Format::Atom => {
+            let (feed, warnings) = atom::Feed::serialize_lenient(input)?;
+            Ok((FeedType::Atom(Box::new(feed)), warnings))
+        }
+        // JSON has nothing to recover from, it reads or it doesn't
+        Format::Json => Ok((
+            FeedType::Json(Box::new(json::Feed::serialize(input)?)),
+            vec![],
+        )),
+    }
+}
+
+// A feed along with the Content-Type it was served with
+type WebFeed = (Box<dyn BufRead>, Option<String>);
+
+fn get_web_feed(source: &str) -> Result<WebFeed, Box<dyn Error>> {
+    let body = get(source)?;
+    let content_type = body
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(str::to_owned);
+    let charset = content_type.as_deref().and_then(charset);
+    Ok((feed_reader(body, charset)?, content_type))
+}
+
+// Every feed is read through here, so whatever encoding it is in greyxml is
+// only ever handed UTF-8
+fn feed_reader<R: Read + 'static>(
+    source: R,
+    charset: Option<&str>,
+) -> Result<Box<dyn BufRead>, Box<dyn Error>> {
+    Ok(Box::new(DecodingReader::new(BufReader::new(source), charset)?))
+}
+
+// The charset parameter of a Content-Type header
+fn charset(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Some(value.trim().trim_matches('"'))
+    })
 }
 
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>, Box<dyn Error>> {
@@ -194,83 +282,64 @@ fn restore_terminal(
 
 struct App<'a> {
     feeds: Vec<FeedType>,
+    // What had to be recovered from to read each feed
+    warnings: Vec<Vec<FeedError>>,
+    // Where each feed was read from, to read it again
+    sources: Vec<String>,
+    // When each feed was last read, to know when it is due again
+    fetched: Vec<DateTime<Utc>>,
     feeds_list: StatefulList<(String, usize)>,
     active_feed: usize,
     feed_items: StatefulList<(String, usize)>,
     active_window: usize,
     active_feed_changed: bool,
     show_popup: bool,
+    // Whether the content pane shows what was wrong with the feed instead
+    show_warnings: bool,
     input: String,
+    // Why the last feed added from the popup could not be read
+    error: Option<String>,
+    // How the last refresh went, when there is something to say about it
+    status: Option<String>,
     terminal: &'a mut Terminal<CrosstermBackend<Stdout>>,
 }
 
 impl<'a> App<'a> {
     pub fn new(
         feeds: Vec<FeedType>,
+        warnings: Vec<Vec<FeedError>>,
+        sources: Vec<String>,
         terminal: &'a mut Terminal<CrosstermBackend<Stdout>>,
     ) -> App<'a> {
         let feeds_list = StatefulList::with_items(
             feeds
                 .iter()
                 .enumerate()
-                .map(|(index, feed)| match feed {
-                    FeedType::Rss(rss) => (rss.channel.data.title.data.clone(), index),
-                    FeedType::Atom(atom) => (atom.contents.title.data.clone(), index),
-                })
+                .map(|(index, feed)| (feed.title(), index))
                 .collect(),
             true,
         );
         let Some(active_feed_index) = feeds_list.state.selected() else {
             panic!("No feed was active by default");
         };
-        let feed_items = StatefulList::with_items(
-            match feeds[active_feed_index] {
-                FeedType::Rss(ref rss) => rss
-                    .channel
-                    .data
-                    .items
-                    .iter()
-                    .enumerate()
-                    .map(|(index, item)| {
-                        if let Some(ref title) = item.data.title {
-                            (title.data.clone(), index)
-                        } else {
-                            if let Some(ref date) = item.data.pub_date {
-                                (date.data.clone(), index)
-                            } else {
-                                (String::new(), 0)
-                            }
-                        }
-                    })
-                    .filter(|(string, _)| !string.is_empty())
-                    .collect(),
-                FeedType::Atom(ref atom) => atom
-                    .contents
-                    .entries
-                    .iter()
-                    .enumerate()
-                    .map(|(index, item)| {
-                        if let Some(ref entry) = item.data {
-                            (entry.title.data.clone(), index)
-                        } else {
-                            (String::new(), index)
-                        }
-                    })
-                    .filter(|(string, _)| !string.is_empty())
-                    .collect(),
-            },
-            false,
-        );
+        let feed_items = StatefulList::with_items(item_labels(&feeds[active_feed_index]), false);
+        let fetched = vec![Utc::now(); feeds.len()];
 
         Self {
             feeds,
+            warnings,
+            sources,
+            fetched,
             feeds_list,
             active_feed: active_feed_index,
             feed_items,
             active_window: 0,
             active_feed_changed: false,
             show_popup: false,
+            show_warnings: false,
             input: String::new(),
+            error: None,
+            status: None,
             terminal,
         }
     }
@@ -285,44 +354,8 @@ impl<'a> App<'a> {
                 self.feeds_list.active = true;
                 self.feed_items.active = false;
                 if self.active_feed_changed {
-                    self.feed_items = StatefulList::with_items(
-                        match self.feeds[self.active_feed] {
-                            FeedType::Rss(ref rss) => rss
-                                .channel
-                                .data
-                                .items
-                                .iter()
-                                .enumerate()
-                                .map(|(index, item)| {
-                                    if let Some(ref title) = item.data.title {
-                                        (title.data.clone(), index)
-                                    } else {
-                                        if let Some(ref date) = item.data.pub_date {
-                                            (date.data.clone(), index)
-                                        } else {
-                                            (String::new(), 0)
-                                        }
-                                    }
-                                })
-                                .filter(|(string, _)| !string.is_empty())
-                                .collect(),
-                            FeedType::Atom(ref atom) => atom
-                                .contents
-                                .entries
-                                .iter()
-                                .enumerate()
-                                .map(|(index, item)| {
-                                    if let Some(ref entry) = item.data {
-                                        (entry.title.data.clone(), index)
-                                    } else {
-                                        (String::new(), index)
-                                    }
-                                })
-                                .filter(|(string, _)| !string.is_empty())
-                                .collect(),
-                        },
-                        false,
-                    );
+                    self.feed_items =
+                        StatefulList::with_items(item_labels(&self.feeds[self.active_feed]), false);
                     self.active_feed_changed = false;
                 }
             } else if self.active_window == 1 {
@@ -335,6 +368,7 @@ impl<'a> App<'a> {
             if self.events()? {
                 break;
             }
+            self.refresh_due();
             self.render()?;
         }
         Ok(())
@@ -360,28 +394,24 @@ impl<'a> App<'a> {
                                     self.show_popup = false;
                                     return Ok(false);
                                 }
-                                if !self.input.starts_with("http://")
-                                    && !self.input.starts_with("https://")
-                                {
+                                if !is_web(&self.input) {
                                     self.show_popup = false;
                                     return Ok(false);
                                 }
-                                let input = get_web_feed(&self.input)?;
-                                let feed = if self.input.ends_with(".atom") {
-                                    FeedType::Atom(atom::Feed::serialize(&input)?)
-                                } else {
-                                    FeedType::Rss(rss::Feed::serialize(&input)?)
-                                };
-                                self.feeds_list.items.push(match feed {
-                                    FeedType::Rss(ref rss) => {
-                                        (rss.channel.data.title.data.clone(), self.feeds.len())
-                                    }
-                                    FeedType::Atom(ref atom) => {
-                                        (atom.contents.title.data.clone(), self.feeds.len())
+                                let (feed, warnings) = match load_feed(&self.input) {
+                                    Ok(feed) => feed,
+                                    Err(e) => {
+                                        self.error = Some(e.to_string());
+                                        return Ok(false);
                                     }
-                                });
+                                };
+                                self.feeds_list.items.push((feed.title(), self.feeds.len()));
                                 self.feeds.push(feed);
+                                self.warnings.push(warnings);
+                                self.sources.push(self.input.clone());
+                                self.fetched.push(Utc::now());
                                 self.show_popup = false;
+                                self.error = None;
                             }
                             _ => {}
                         }
@@ -417,6 +447,10 @@ impl<'a> App<'a> {
                             KeyCode::F(1) => {
                                 self.show_popup = !self.show_popup;
                             }
+                            KeyCode::F(2) => {
+                                self.show_warnings = !self.show_warnings;
+                            }
+                            KeyCode::F(5) => self.refresh(self.active_feed),
                             _ => {}
                         }
                     }
@@ -426,12 +460,48 @@ impl<'a> App<'a> {
         Ok(false)
     }
 
+    // Reads the feed at `index` again. Pressing F5 always gets here, only the
+    // scheduled refreshes in `refresh_due` wait for a feed to be due.
+    fn refresh(&mut self, index: usize) {
+        self.fetched[index] = Utc::now();
+        match load_feed(&self.sources[index]) {
+            Ok((feed, warnings)) => {
+                self.feeds_list.items[index].0 = feed.title();
+                if index == self.active_feed {
+                    self.feed_items = StatefulList::with_items(item_labels(&feed), false);
+                }
+                self.feeds[index] = feed;
+                self.warnings[index] = warnings;
+                self.status = None;
+            }
+            // Scheduled refreshes happen to any feed, so say which it was
+            Err(e) => self.status = Some(format!("{}: {e}", self.feeds_list.items[index].0)),
+        }
+    }
+
+    // Refreshes every feed whose <ttl> is up, unless it asks not to be
+    // fetched at this hour or on this day
+    fn refresh_due(&mut self) {
+        let now = Utc::now();
+        for index in 0..self.feeds.len() {
+            if self.feeds[index].due(self.fetched[index], now) {
+                self.refresh(index);
+            }
+        }
+    }
+
     fn render(&mut self) -> Result<(), Box<dyn Error>> {
         self.terminal.draw(|f| {
             let global_block = Block::new().borders(Borders::ALL).title("Kisserss");
             let inner_block = Block::new().borders(Borders::TOP);
             let feeds_block = Block::new().borders(Borders::RIGHT);
-            let content_block = Block::new().borders(Borders::TOP).title("Content");
+            let warnings = &self.warnings[self.active_feed];
+            let content_title = match warnings.len() {
+                0 => "Content".to_string(),
+                1 => "Content (1 warning, F2)".to_string(),
+                count => format!("Content ({count} warnings, F2)"),
+            };
+            let content_block = Block::new().borders(Borders::TOP).title(content_title);
             let outer_layout = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Max(1), Constraint::Min(1)].as_ref())
@@ -452,39 +522,94 @@ impl<'a> App<'a> {
             let feeds_list = feeds_clone.to_list_tuple();
             let items_list = feed_items_clone.to_list_tuple();
 
-            let content = if let Some(selected) = self.feed_items.state.selected() {
+            let selected = self
+                .feed_items
+                .state
+                .selected()
+                .filter(|_| !self.show_warnings);
+            let content = if let Some(selected) = selected {
                 let feed = &self.feeds[self.active_feed];
                 match feed {
                     FeedType::Rss(rss) => {
-                        if let Some(ref desc) = rss.channel.data.items
-                            [self.feed_items.items[selected].1]
-                            .data
-                            .description
-                        {
-                            let decoded = decode(&desc.data, false);
-                            Paragraph::new(format(decoded))
-                        } else {
-                            Paragraph::new(String::new())
+                        let item = &rss.channel.data.items[self.feed_items.items[selected].1].data;
+                        let mut lines: Vec<Line> =
+                            byline(item.author(), item.date()).into_iter().collect();
+                        lines.extend(
+                            item.enclosures
+                                .iter()
+                                .flatten()
+                                .map(|enclosure| enclosure_line(&enclosure.data)),
+                        );
+                        if let Some(ref media) = item.media {
+                            lines.extend(media_lines(media));
+                        }
+                        if let Some(ref desc) = item.description {
+                            if !lines.is_empty() {
+                                lines.push(Line::default());
+                            }
+                            lines.extend(format(desc.data.clone()));
                         }
+                        Paragraph::new(lines)
                     }
                     FeedType::Atom(atom) => {
                         if let Some(ref entry) =
                             atom.contents.entries[self.feed_items.items[selected].1].data
                         {
-                            let decoded = decode(&entry.content.data, false);
-                            Paragraph::new(format(decoded))
+                            let mut lines: Vec<Line> =
+                                byline(entry.author(), entry.date()).into_iter().collect();
+                            lines.extend(entry.media.iter().flat_map(media_lines));
+                            if !entry.content.data.is_empty() {
+                                if !lines.is_empty() {
+                                    lines.push(Line::default());
+                                }
+                                lines.extend(format(entry.content.data.clone()));
+                            }
+                            Paragraph::new(lines)
                         } else {
                             Paragraph::new(String::new())
                         }
                     }
+                    FeedType::Json(json) => {
+                        let item = &json.items[self.feed_items.items[selected].1];
+                        let mut lines: Vec<Line> =
+                            byline(item.author(), item.date()).into_iter().collect();
+                        lines.extend(item.attachments.iter().map(attachment_line));
+                        let content = match (&item.content_html, &item.content_text) {
+                            (Some(html), _) => format(html.clone()),
+                            (None, Some(text)) => text
+                                .lines()
+                                .map(|line| Line::from(line.to_string()))
+                                .collect(),
+                            (None, None) => vec![],
+                        };
+                        if !content.is_empty() && !lines.is_empty() {
+                            lines.push(Line::default());
+                        }
+                        lines.extend(content);
+                        Paragraph::new(lines)
+                    }
                 }
             } else {
-                Paragraph::new(String::new())
+                // Anything wrong with the feed, when asked for or until an
+                // item is picked
+                Paragraph::new(
+                    warnings
+                        .iter()
+                        .map(|warning| Line::from(format!("Warning: {warning}")))
+                        .collect::<Vec<_>>(),
+                )
             };
 
             f.render_widget(global_block.clone(), f.size());
             f.render_widget(inner_block.clone(), outer_layout[1]);
-            f.render_widget(Paragraph::new("F1: Add Feed"), outer_layout[0]);
+            let keys = "F1: Add Feed  F2: Warnings  F5: Refresh";
+            f.render_widget(
+                Paragraph::new(match self.status {
+                    Some(ref status) => format!("{keys}  {status}"),
+                    None => keys.to_string(),
+                }),
+                outer_layout[0],
+            );
             f.render_stateful_widget(
                 feeds_list.block(feeds_block.clone()),
                 inner_layout[0],
@@ -498,7 +623,8 @@ impl<'a> App<'a> {
                 content_layout[1],
             );
             if self.show_popup {
-                let block = Block::default().title("Popup").borders(Borders::ALL);
+                let title = self.error.as_deref().unwrap_or("Popup");
+                let block = Block::default().title(title).borders(Borders::ALL);
                 let area = centered_rect(70, 10, f.size());
                 let center = centered_rect(90, 100, block.inner(area));
                 f.render_widget(Clear, area);
@@ -537,32 +663,308 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         )
         .split(popup_layout[1])[1]
 }
+// Who wrote an item and when, for the top of its content
+fn byline(author: Option<&str>, date: Option<&Date>) -> Option<Line<'static>> {
+    match (author, date.map(when)) {
+        (Some(author), Some(date)) => Some(Line::from(format!("By {author}, {date}"))),
+        (Some(author), None) => Some(Line::from(format!("By {author}"))),
+        (None, Some(date)) => Some(Line::from(date)),
+        (None, None) => None,
+    }
+}
+
+// A date as the feed wrote it, followed by how long ago that was when it
+// could be read
+fn when(date: &Date) -> String {
+    let Some(time) = date.time else {
+        return date.text.trim().to_string();
+    };
+    let elapsed = Utc::now().signed_duration_since(time);
+    let relative = if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        ago(elapsed.num_minutes(), "minute")
+    } else if elapsed.num_days() < 1 {
+        ago(elapsed.num_hours(), "hour")
+    } else if elapsed.num_days() < 365 {
+        ago(elapsed.num_days(), "day")
+    } else {
+        ago(elapsed.num_days() / 365, "year")
+    };
+    format!("{} ({relative})", date.text.trim())
+}
+
+fn ago(count: i64, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {unit} ago")
+    } else {
+        format!("{count} {unit}s ago")
+    }
+}
+
+// What the items of a feed are listed as, along with where they are in it.
+// Items with nothing to show for them are left out.
+fn item_labels(feed: &FeedType) -> Vec<(String, usize)> {
+    match feed {
+        FeedType::Rss(rss) => rss
+            .channel
+            .data
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                if let Some(ref title) = item.data.title {
+                    (item_label(&item.data, &title.data), index)
+                } else {
+                    if let Some(date) = item.data.date() {
+                        (item_label(&item.data, &date.text), index)
+                    } else {
+                        (String::new(), 0)
+                    }
+                }
+            })
+            .filter(|(string, _)| !string.is_empty())
+            .collect(),
+        FeedType::Atom(atom) => atom
+            .contents
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                if let Some(ref entry) = item.data {
+                    (entry.title.data.clone(), index)
+                } else {
+                    (String::new(), index)
+                }
+            })
+            .filter(|(string, _)| !string.is_empty())
+            .collect(),
+        FeedType::Json(json) => json
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (json_item_label(item), index))
+            .filter(|(string, _)| !string.is_empty())
+            .collect(),
+    }
+}
+
+// An item's title, marked with its season, episode and length when it is
+// an episode of a podcast
+fn item_label(item: &rss::Item, title: &str) -> String {
+    let Some(ref episode) = item.podcast else {
+        return title.to_string();
+    };
+    let mut label = String::new();
+    if let Some(season) = episode.season {
+        label.push_str(&format!("S{season}"));
+    }
+    if let Some(number) = episode.episode {
+        label.push_str(&format!("E{number}"));
+    }
+    if !label.is_empty() {
+        label.push(' ');
+    }
+    label.push_str(title);
+    if let Some(ref duration) = episode.duration {
+        label.push_str(&format!(" ({})", play_time(duration.0)));
+    }
+    label
+}
+
+// A JSON Feed item's title, or what it says when it is too short to have one.
+// Items with nothing but HTML to them are labelled with its first line of
+// text, and failing that their id.
+fn json_item_label(item: &json::Item) -> String {
+    let label = item
+        .title
+        .as_ref()
+        .or(item.summary.as_ref())
+        .or(item.content_text.as_ref())
+        .map(|text| text.lines().next().unwrap_or_default().to_string());
+    match label {
+        Some(label) => label,
+        None => match item.date() {
+            Some(date) => date.text.clone(),
+            None => item
+                .content_html
+                .as_deref()
+                .and_then(first_line)
+                .unwrap_or_else(|| item.id.clone()),
+        },
+    }
+}
+
+// The first line of text in some HTML that has any, where every element
+// other than the ones marking up text starts a new line
+fn first_line(html: &str) -> Option<String> {
+    const INLINE: [&str; 8] = ["a", "b", "i", "em", "strong", "span", "code", "small"];
+    let lexed = lex(html).ok()?;
+    let tokens = tokenize_html(&mut lexed.into_iter()).ok()?;
+    let mut text = String::new();
+    for token in &tokens {
+        match token {
+            greyxml::Tokens::Text(part) | greyxml::Tokens::CData(part) => {
+                // The text comes trimmed, so the space around inline elements
+                // has to be put back
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push(' ');
+                }
+                text.push_str(part);
+            }
+            greyxml::Tokens::OpenNode(node) | greyxml::Tokens::CloseNode(node)
+                if !INLINE.contains(&node.local_name()) =>
+            {
+                text.push('\n')
+            }
+            _ => {}
+        }
+    }
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+// What an enclosure is and how big, then where to get it
+fn enclosure_line(enclosure: &rss::Enclosure) -> Line<'static> {
+    let mime_type = if enclosure.mime_type.is_empty() {
+        "unknown type"
+    } else {
+        &enclosure.mime_type
+    };
+    let size = enclosure.length.map_or("unknown size".into(), file_size);
+    Line::from(format!("Enclosure: {mime_type}, {size}: {}", enclosure.url))
+}
+
+// What an attachment is and how big, then where to get it
+fn attachment_line(attachment: &json::Attachment) -> Line<'static> {
+    let mut about = vec![attachment.mime_type.clone()];
+    about.extend(attachment.size_in_bytes.map(file_size));
+    about.extend(
+        attachment
+            .duration_in_seconds
+            .map(|seconds| play_time(seconds as u32)),
+    );
+    let title = attachment
+        .title
+        .as_ref()
+        .map_or(String::new(), |title| format!("{title}, "));
+    Line::from(format!(
+        "Attachment: {title}{}: {}",
+        about.join(", "),
+        attachment.url
+    ))
+}
+
+// A number of bytes in the largest unit it is at least one of
+fn file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+// The media an item links to and its thumbnails, with the media's own
+// description after them
+fn media_lines(media: &Media) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    for content in media.contents() {
+        let mut line = String::from("Media: ");
+        line.push_str(
+            content
+                .mime_type
+                .as_deref()
+                .or(content.medium.as_deref())
+                .unwrap_or("unknown type"),
+        );
+        if let Some(size) = dimensions(content.width, content.height) {
+            line.push_str(&format!(", {size}"));
+        }
+        if let Some(duration) = content.duration {
+            line.push_str(&format!(", {}", play_time(duration)));
+        }
+        if let Some(ref url) = content.url {
+            line.push_str(&format!(": {url}"));
+        }
+        lines.push(Line::from(line));
+    }
+    for details in media.details() {
+        for thumbnail in details.thumbnails.iter().flatten() {
+            let thumbnail = &thumbnail.data;
+            let size = dimensions(thumbnail.width, thumbnail.height);
+            let size = size.map(|size| format!(" {size}")).unwrap_or_default();
+            lines.push(Line::from(format!("Thumbnail{size}: {}", thumbnail.url)));
+        }
+        if let Some(ref player) = details.player {
+            lines.push(Line::from(format!("Player: {}", player.data.url)));
+        }
+        for credit in details.credits.iter().flatten() {
+            lines.push(Line::from(format!("Credit: {}", credit.data)));
+        }
+    }
+    if let Some(description) = media
+        .details()
+        .find_map(|details| details.description.as_ref())
+    {
+        lines.push(Line::default());
+        for s in description.data.split('\n') {
+            lines.push(Line::from(s.to_string()));
+        }
+    }
+    lines
+}
+
+fn dimensions(width: Option<u32>, height: Option<u32>) -> Option<String> {
+    Some(format!("{}x{}", width?, height?))
+}
+
+// Seconds as h:mm:ss, or m:ss when it is under an hour
+fn play_time(seconds: u32) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
 // This isn't ideal
 fn format(content: String) -> Vec<Line<'static>> {
     let mut lines = vec![];
-    let lexed;
-    match lex(&content) {
-        Ok(lexeded) => lexed = lexeded,
+    let lexed = match lex(&content) {
+        Ok(lexed) => lexed,
         Err(e) => {
-            lines.push(Line::from(vec![Span::raw(
-                "Could not lex content {content} due to {e:?}",
-            )]));
+            lines.push(Line::from(vec![Span::raw(format!(
+                "Could not lex content at {e}"
+            ))]));
+            for s in content.split('\n') {
+                lines.push(Line::from(vec![Span::raw(s.to_string())]));
+            }
             return lines;
         }
-    }
-    let tokens;
-    match tokenize(&mut lexed.into_iter()) {
-        Ok(tkns) => tokens = tkns,
+    };
+    let tokens = match tokenize_html(&mut lexed.into_iter()) {
+        Ok(tokens) => tokens,
         Err(e) => {
-            lines.push(Line::from(vec![Span::raw(
-                "Could not tokenize content {content} due to {e:?}",
-            )]));
+            lines.push(Line::from(vec![Span::raw(format!(
+                "Could not tokenize content at {e}"
+            ))]));
+            for s in content.split('\n') {
+                lines.push(Line::from(vec![Span::raw(s.to_string())]));
+            }
             return lines;
         }
-    }
+    };
     let mut peekable = tokens.iter().peekable();
     let mut in_node: Vec<String> = vec![];
-    let mut is_href = false;
     let mut current_text = String::new();
     while let Some(token) = peekable.peek() {
         let token = *token;
@@ -573,47 +975,36 @@ fn format(content: String) -> Vec<Line<'static>> {
                     current_text.push('\n');
                     continue;
                 }
-                if node == "a" && !in_node.contains(&String::from("p")) {
-                    if !current_text.is_empty() {
-                        current_text.push('\n');
-                    }
+                if node == "a" && !in_node.contains(&String::from("p")) && !current_text.is_empty()
+                {
+                    current_text.push('\n');
                 }
                 if node == "span" {
                     continue;
                 }
                 in_node.push(node.to_string());
             }
-            greyxml::Tokens::Text(text) => {
+            greyxml::Tokens::Text(text) | greyxml::Tokens::CData(text) => {
                 current_text.push_str(text);
             }
-            greyxml::Tokens::CloseNode(node) => {
-                if Some(node) == in_node.last() {
-                    in_node.pop();
-                    let decoded = decode(&current_text, true);
-                    let split = decoded.split('\n');
-                    for s in split {
-                        lines.push(Line::from(vec![Span::raw(s.to_string())]));
-                    }
-                    current_text = String::new();
+            greyxml::Tokens::CloseNode(node)
+                if Some(node.as_str()) == in_node.last().map(String::as_str) =>
+            {
+                in_node.pop();
+                let split = current_text.split('\n');
+                for s in split {
+                    lines.push(Line::from(vec![Span::raw(s.to_string())]));
                 }
+                current_text = String::new();
             }
             _ => {}
         }
     }
+    // Text after the last node closed
+    if !current_text.is_empty() {
+        for s in current_text.split('\n') {
+            lines.push(Line::from(vec![Span::raw(s.to_string())]));
+        }
+    }
     lines
 }
-fn decode(content: &str, is_content: bool) -> String {
-    let mut output = if is_content {
-        content.replace("&amp;", "&")
-    } else {
-        String::from(content)
-    };
-    output = output.replace("&#xA;", "\n");
-    output = output.replace("&lt;", "<");
-    output = output.replace("&gt;", ">");
-    output = output.replace("&apos;", "'");
-    output = output.replace("&#39;", "'");
-    output = output.replace("&quot;", "\"");
-    output = output.replace("&#34;", "\"");
-    output
-}
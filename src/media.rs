@@ -0,0 +1,91 @@
+use crate::elements::Element;
+
+// https://www.rssboard.org/media-rss
+// Media RSS elements can be added to an RSS item or an Atom entry, as YouTube
+// does. There is no one element holding them all, so this gathers whichever
+// of them were found directly inside the item.
+#[derive(Debug, Default)]
+pub struct Media {
+    pub contents: Option<Vec<Element<Content>>>,
+    // Each group is one piece of media offered in several versions
+    pub groups: Option<Vec<Element<Group>>>,
+    pub details: Details,
+}
+
+impl Media {
+    // Every content, whether it is in a group or not
+    pub fn contents(&self) -> impl Iterator<Item = &Content> {
+        let grouped = self
+            .groups
+            .iter()
+            .flatten()
+            .flat_map(|group| group.data.contents.iter().flatten());
+        self.contents
+            .iter()
+            .flatten()
+            .chain(grouped)
+            .map(|content| &content.data)
+    }
+
+    // The details given for the item, each group and each content, broadest first
+    pub fn details(&self) -> impl Iterator<Item = &Details> {
+        let groups = self
+            .groups
+            .iter()
+            .flatten()
+            .map(|group| &group.data.details);
+        let contents = self.contents().map(|content| &content.details);
+        std::iter::once(&self.details).chain(groups).chain(contents)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Group {
+    pub contents: Option<Vec<Element<Content>>>,
+    pub details: Details,
+}
+
+// https://www.rssboard.org/media-rss#media-content
+// Read from its attributes, anything numeric that doesn't parse is left out
+#[derive(Debug, Default)]
+pub struct Content {
+    pub url: Option<String>,
+    pub mime_type: Option<String>,
+    // One of image, audio, video, document or executable
+    pub medium: Option<String>,
+    // In seconds
+    pub duration: Option<u32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub details: Details,
+}
+
+// https://www.rssboard.org/media-rss#optional-elements
+// The optional elements, which apply to whatever they were found in
+#[derive(Debug, Default)]
+pub struct Details {
+    pub title: Option<Element<String>>,
+    pub description: Option<Element<String>>,
+    pub thumbnails: Option<Vec<Element<Thumbnail>>>,
+    // Who made the media, their role is in the `role` attribute
+    pub credits: Option<Vec<Element<String>>>,
+    pub player: Option<Element<Player>>,
+}
+
+// https://www.rssboard.org/media-rss#media-thumbnails
+#[derive(Debug, Default)]
+pub struct Thumbnail {
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    // How far into the media it was taken, as an NTP time such as 12:05:01.123
+    pub time: Option<String>,
+}
+
+// https://www.rssboard.org/media-rss#media-player
+#[derive(Debug, Default)]
+pub struct Player {
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
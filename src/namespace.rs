@@ -0,0 +1,21 @@
+// Namespaces feeds commonly pull elements in from, matched by URI rather than
+// by whatever prefix the feed happened to bind them to
+
+// https://web.resource.org/rss/1.0/modules/content/
+pub const CONTENT: &str = "http://purl.org/rss/1.0/modules/content/";
+// https://www.w3.org/TR/rdf-syntax-grammar/#section-Namespace
+pub const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+// https://web.resource.org/rss/1.0/spec#s5.2
+pub const RSS_1_0: &str = "http://purl.org/rss/1.0/";
+// https://www.rssboard.org/rss-0-9-0
+pub const RSS_0_90: &str = "http://my.netscape.com/rdf/simple/0.9/";
+// https://datatracker.ietf.org/doc/html/rfc4287#section-2
+pub const ATOM: &str = "http://www.w3.org/2005/Atom";
+// https://www.dublincore.org/specifications/dublin-core/dces/
+pub const DUBLIN_CORE: &str = "http://purl.org/dc/elements/1.1/";
+// https://www.rssboard.org/media-rss#namespace-declaration
+pub const MEDIA: &str = "http://search.yahoo.com/mrss/";
+// https://help.apple.com/itc/podcasts_connect/#/itcb54353390
+pub const ITUNES: &str = "http://www.itunes.com/dtds/podcast-1.0.dtd";
+// https://podcastindex.org/namespace/1.0
+pub const PODCAST: &str = "https://podcastindex.org/namespace/1.0";
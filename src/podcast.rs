@@ -0,0 +1,81 @@
+use crate::elements::Element;
+
+// The podcast metadata of a channel, from Apple's iTunes namespace
+// https://help.apple.com/itc/podcasts_connect/#/itcb54353390
+#[derive(Debug, Default)]
+pub struct Show {
+    pub author: Option<Element<String>>,
+    pub image: Option<Element<Image>>,
+    pub explicit: Option<Element<bool>>,
+    pub summary: Option<Element<String>>,
+}
+
+// The podcast metadata of an item, from the iTunes namespace and the Podcast
+// Index's https://podcastindex.org/namespace/1.0
+#[derive(Debug, Default)]
+pub struct Episode {
+    pub author: Option<Element<String>>,
+    // These three are left out when they hold something else, such as
+    // "45 min" or "bonus", rather than failing the whole feed
+    pub duration: Option<Duration>,
+    pub episode: Option<u32>,
+    pub season: Option<u32>,
+    // Artwork for just this episode, rather than the show's
+    pub image: Option<Element<Image>>,
+    pub explicit: Option<Element<bool>>,
+    pub summary: Option<Element<String>>,
+    // Often offered in more than one format
+    pub transcripts: Option<Vec<Element<Transcript>>>,
+    pub chapters: Option<Element<Chapters>>,
+}
+
+// The length of an episode in seconds, written either as a number of seconds
+// or as HH:MM:SS or MM:SS
+#[derive(Debug, Default)]
+pub struct Duration(pub u32);
+
+impl Duration {
+    // Any fraction of a second on the end is dropped
+    pub fn parse(text: &str) -> Option<Duration> {
+        let text = match text.trim().split_once('.') {
+            Some((whole, fraction)) if fraction.chars().all(|c| c.is_ascii_digit()) => whole,
+            Some(_) => return None,
+            None => text.trim(),
+        };
+        let mut seconds = 0u32;
+        let mut fields = 0;
+        for field in text.split(':') {
+            fields += 1;
+            if fields > 3 {
+                return None;
+            }
+            seconds = seconds.checked_mul(60)?.checked_add(field.parse().ok()?)?;
+        }
+        Some(Duration(seconds))
+    }
+}
+
+// The image is in its `href` attribute
+#[derive(Debug, Default)]
+pub struct Image {
+    pub href: String,
+}
+
+// https://podcastindex.org/namespace/1.0#transcript
+#[derive(Debug, Default)]
+pub struct Transcript {
+    pub url: String,
+    // Such as text/vtt or application/x-subrip
+    pub mime_type: String,
+    pub language: Option<String>,
+    // Set to "captions" when the transcript is timed for showing as subtitles
+    pub rel: Option<String>,
+}
+
+// https://podcastindex.org/namespace/1.0#chapters
+// Links to a JSON file of chapters rather than listing them
+#[derive(Debug, Default)]
+pub struct Chapters {
+    pub url: String,
+    pub mime_type: String,
+}
@@ -1,67 +1,201 @@
-use crate::elements::Element;
-use greyxml::{lex, tokenize, Tokens};
+use std::io::BufRead;
 
-type RssResult<T> = Result<T, Box<dyn std::error::Error>>;
+use crate::date::Date;
+use crate::dublin_core::DublinCore;
+use crate::elements::{rdf_attribute, read_document, Document, Element, Spanned, TokenStream};
+use crate::error::FeedError;
+use crate::media::Media;
+use crate::namespace;
+use crate::podcast::{Episode, Show};
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc, Weekday};
+use greyxml::{Name, Reader, Tokens};
+
+type RssResult<T> = Result<T, FeedError>;
+
+// Which RSS a feed is. 0.90 and 1.0 are RDF documents, every other version is
+// named by the version attribute of an <rss> root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Version {
+    Rss090,
+    Rss091,
+    Rss092,
+    Rss093,
+    Rss094,
+    Rss10,
+    Rss20,
+    // Whatever else the version attribute held, if anything, which is read
+    // the same as 2.0
+    Unknown(String),
+}
+
+impl Version {
+    pub fn parse(version: &str) -> Version {
+        match version.trim() {
+            "0.9" | "0.90" => Version::Rss090,
+            "0.91" => Version::Rss091,
+            "0.92" => Version::Rss092,
+            "0.93" => Version::Rss093,
+            "0.94" => Version::Rss094,
+            "1.0" => Version::Rss10,
+            "2.0" | "2.0.1" => Version::Rss20,
+            other => Version::Unknown(other.to_string()),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Feed {
-    pub version: f32,
+    pub version: Version,
     pub specs: Option<Vec<(String, String)>>,
     pub channel: Element<Channel>,
 }
 
 impl Feed {
-    pub fn serialize(input: &str) -> RssResult<Feed> {
-        let lexed = lex(input.into())?;
-        let tokens = tokenize(&mut lexed.into_iter())?;
+    pub fn serialize<R: BufRead>(reader: R) -> RssResult<Feed> {
+        read_document(Reader::new(reader), false).map(|(feed, _)| feed)
+    }
 
-        let mut tokens_iter = tokens.into_iter().peekable();
+    // Reads feeds too broken to be XML as best it can, along with everything
+    // that had to be recovered from to read it
+    pub fn serialize_lenient<R: BufRead>(reader: R) -> RssResult<(Feed, Vec<FeedError>)> {
+        read_document(Reader::lenient(reader), true)
+    }
+
+    // RSS 0.90 and 1.0 have the channel's items, image and text input
+    // alongside it under an <rdf:RDF> root, rather than inside it
+    fn serialize_rdf<'a, I>(root: Name<'a>, tokens_iter: &mut TokenStream<I>) -> RssResult<Feed>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        let span = tokens_iter.span().cloned();
+        let rdf: Element<Rdf> = Element::read(root, tokens_iter)?;
+        let Rdf {
+            channel,
+            image,
+            text_input,
+            items,
+        } = rdf.data;
+        let Some(mut channel) = channel else {
+            return Err(FeedError::Missing("channel".into(), span));
+        };
+
+        // The channel only points at these with rdf:resource
+        if image.is_some() {
+            channel.data.image = image;
+        }
+        if text_input.is_some() {
+            channel.data.text_input = text_input;
+        }
+        // RSS 1.0 gives the order of the items in the channel's rdf:Seq, any
+        // it leaves out keep their place in the document after the rest
+        let Channel {
+            items: channel_items,
+            sequence,
+            ..
+        } = &mut channel.data;
+        channel_items.extend(items);
+        channel_items.sort_by_key(|item| {
+            rdf_attribute(&item.attributes, "about")
+                .and_then(|about| sequence.iter().position(|resource| resource == about))
+                .unwrap_or(sequence.len())
+        });
+
+        let is_0_90 = rdf
+            .attributes
+            .iter()
+            .any(|(name, value)| name == "xmlns" && value == namespace::RSS_0_90);
+        Ok(Feed {
+            version: if is_0_90 {
+                Version::Rss090
+            } else {
+                Version::Rss10
+            },
+            specs: if rdf.attributes.is_empty() {
+                None
+            } else {
+                Some(rdf.attributes)
+            },
+            channel,
+        })
+    }
+}
 
-        let Some(rss) = tokens_iter.find(|t| t == &Tokens::OpenNode("rss".into())) else {
-            //FIXME: Handle error properly
-            panic!("Could not find rss node");
+impl Document for Feed {
+    fn serialize_tokens<'a, I>(tokens_iter: &mut TokenStream<I>) -> RssResult<Feed>
+    where
+        I: Iterator<Item = Spanned<'a>>,
+    {
+        let root = tokens_iter.find_map(|token| match token {
+            Tokens::OpenNode(node) if node == "rss" || is_rdf(&node) => Some(node),
+            _ => None,
+        });
+        let Some(root) = root else {
+            return Err(FeedError::Missing("rss".into(), None));
         };
+        if is_rdf(&root) {
+            return Feed::serialize_rdf(root, tokens_iter);
+        }
 
-        if let None = tokens_iter.peek() {
-            panic!("No more tokens?");
+        let span = tokens_iter.span().cloned();
+        if tokens_iter.peek().is_none() {
+            return Err(FeedError::Unclosed("rss".into(), span));
         }
 
-        let mut version = 0.0;
+        let mut version = Version::Unknown(String::new());
         let mut specs = vec![];
 
-        while let Some(token) = tokens_iter.peek() {
-            let token = token.clone();
-            match token {
-                Tokens::ParameterName(name) => {
-                    if name == "version" {
-                        tokens_iter.next();
-                        if let Some(Tokens::ParameterValue(v)) = tokens_iter.next() {
-                            version = v.parse().unwrap();
-                        } else {
-                            version = 0.0;
-                        }
-                        continue;
-                    }
-                    tokens_iter.next();
-                    if let Some(Tokens::ParameterValue(value)) = tokens_iter.next() {
-                        specs.push((name.clone(), value));
-                    }
-                }
-                _ => {
-                    break;
+        while let Some(Tokens::ParameterName(name)) =
+            tokens_iter.next_if(|token| matches!(token, Tokens::ParameterName(_)))
+        {
+            if name == "version" {
+                if let Some(Tokens::ParameterValue(v)) = tokens_iter.next() {
+                    version = Version::parse(&v);
                 }
+                continue;
+            }
+            if let Some(Tokens::ParameterValue(value)) = tokens_iter.next() {
+                specs.push((name.into(), value.into_owned()));
             }
         }
 
+        let channel = match tokens_iter.next() {
+            Some(Tokens::OpenNode(node)) if node == "channel" => Element::read(node, tokens_iter)?,
+            Some(Tokens::OpenNode(node)) => {
+                return Err(FeedError::UnexpectedElement(
+                    "channel".into(),
+                    node.to_string(),
+                    tokens_iter.span().cloned(),
+                ))
+            }
+            _ => return Err(FeedError::Missing("channel".into(), span)),
+        };
+
         Ok(Feed {
             version,
             specs: if specs.is_empty() { None } else { Some(specs) },
-            // FIXME: Don't use `unwrap()` here
-            channel: Element::<Channel>::serialize(tokens_iter.next().unwrap(), &mut tokens_iter),
+            channel,
         })
     }
 }
 
+// The root element of RSS 0.90 and 1.0, whichever namespace it was bound to
+fn is_rdf(node: &Name) -> bool {
+    node.is(namespace::RDF, "RDF") || node == "rdf:RDF"
+}
+
+// What is under an <rdf:RDF> root, before it is gathered into the channel
+#[derive(Default, Debug)]
+pub(crate) struct Rdf {
+    pub(crate) channel: Option<Element<Channel>>,
+    pub(crate) image: Option<Element<Image>>,
+    pub(crate) text_input: Option<Element<TextInput>>,
+    pub(crate) items: Vec<Element<Item>>,
+}
+
+// The rdf:resource of each <rdf:li> in an RSS 1.0 channel's <items>
+#[derive(Default, Debug)]
+pub(crate) struct Sequence(pub(crate) Vec<String>);
+
 // https://www.rssboard.org/rss-specification
 #[derive(Default, Debug)]
 pub struct Channel {
@@ -73,13 +207,12 @@ pub struct Channel {
     pub copyright: Option<Element<String>>,
     pub managing_editor: Option<Element<String>>,
     pub web_master: Option<Element<String>>,
-    // TODO: Maybe make this a chrono time thing
-    pub pub_date: Option<Element<String>>,
-    pub last_build_date: Option<Element<String>>,
+    pub pub_date: Option<Element<Date>>,
+    pub last_build_date: Option<Element<Date>>,
     pub category: Option<Element<String>>,
     pub generator: Option<Element<String>>,
     pub docs: Option<Element<String>>,
-    pub cloud: Option<Element<String>>,
+    pub cloud: Option<Element<Cloud>>,
     pub ttl: Option<Element<u32>>,
     pub image: Option<Element<Image>>,
     // FIXME: Idk what the format of this is yet
@@ -87,6 +220,10 @@ pub struct Channel {
     pub text_input: Option<Element<TextInput>>,
     pub skip_hours: Option<Element<SkipHours>>,
     pub skip_days: Option<Element<SkipDays>>,
+    pub podcast: Option<Show>,
+    pub dublin_core: Option<DublinCore>,
+    // The order of the items of an RSS 1.0 channel, by their rdf:about
+    pub(crate) sequence: Vec<String>,
 }
 
 // https://www.rssboard.org/rss-specification#hrelementsOfLtitemgt
@@ -97,18 +234,78 @@ pub struct Item {
     pub description: Option<Element<String>>,
     pub author: Option<Element<String>>,
     pub category: Option<Element<String>>,
-    pub enclosure: Option<Element<String>>,
+    // Usually one, though some feeds offer the same episode in several formats
+    pub enclosures: Option<Vec<Element<Enclosure>>>,
     pub guid: Option<Element<String>>,
-    pub pub_date: Option<Element<String>>,
+    pub pub_date: Option<Element<Date>>,
     pub source: Option<Element<String>>,
     // part of https://web.resource.org/rss/1.0/modules/content/
     pub content_encoded: Option<Element<EncodedContent>>,
-    // part of https://www.rssboard.org/media-rss
-    pub media: Option<Element<Media>>,
+    pub media: Option<Media>,
+    pub podcast: Option<Episode>,
+    pub dublin_core: Option<DublinCore>,
 }
 
+// How long a feed that gives no <ttl> is kept before it's read again, in minutes
+pub const DEFAULT_TTL: u32 = 60;
+
+impl Channel {
+    // Whether a scheduled refresh should read the channel again at `now`,
+    // having last read it at `fetched`. It waits out the <ttl> and stays away
+    // from skipHours and skipDays, neither of which hold back refreshing by hand.
+    pub fn due(&self, fetched: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        let ttl = match self.ttl {
+            Some(ref ttl) if ttl.data > 0 => ttl.data,
+            _ => DEFAULT_TTL,
+        };
+        now - fetched >= Duration::minutes(ttl.into()) && !self.skips(now)
+    }
+
+    // Whether the channel asks not to be fetched at `at`, going by its
+    // skipHours and skipDays
+    pub fn skips(&self, at: DateTime<Utc>) -> bool {
+        let hour = at.hour() as u8;
+        let day = Days::from(at.weekday());
+        self.skip_hours
+            .as_ref()
+            .is_some_and(|skip| skip.data.hours.contains(&hour))
+            || self
+                .skip_days
+                .as_ref()
+                .is_some_and(|skip| skip.data.days.contains(&day))
+    }
+}
+
+impl Item {
+    // Who wrote the item, from dc:creator if it has no <author>
+    pub fn author(&self) -> Option<&str> {
+        match self.author {
+            Some(ref author) => Some(&author.data),
+            None => self.dublin_core.as_ref()?.creator(),
+        }
+    }
+
+    // When the item was published, from dc:date if it has no <pubDate>
+    pub fn date(&self) -> Option<&Date> {
+        match self.pub_date {
+            Some(ref date) => Some(&date.data),
+            None => self.dublin_core.as_ref()?.date(),
+        }
+    }
+}
+
+// https://www.rssboard.org/rss-specification#ltenclosuregtSubelementOfLtitemgt
+// Everything is in its attributes, it has no content
 #[derive(Default, Debug)]
-pub struct EncodedContent(pub Vec<Element<String>>, pub(crate) String);
+pub struct Enclosure {
+    pub url: String,
+    // In bytes, left out or given as 0 by plenty of feeds that don't know it
+    pub length: Option<u64>,
+    pub mime_type: String,
+}
+
+#[derive(Default, Debug)]
+pub struct EncodedContent(pub Vec<Element<String>>);
 
 #[derive(Default, Debug)]
 pub struct Image {
@@ -128,13 +325,27 @@ pub struct TextInput {
     pub link: Element<String>,
 }
 
+// https://www.rssboard.org/rss-specification#ltcloudgtSubelementOfLtchannelgt
+// Where to register to be told when the channel changes, all in its attributes
 #[derive(Default, Debug)]
-pub struct SkipHours {
-    // A number between 0 and 23
-    pub hours: [u8; 24],
+pub struct Cloud {
+    pub domain: String,
+    pub port: Option<u16>,
+    pub path: String,
+    pub register_procedure: String,
+    // One of xml-rpc, soap or http-post
+    pub protocol: String,
 }
 
+// https://www.rssboard.org/skipHoursDays
+// The hours in GMT when the channel asks not to be fetched, from 0 to 23.
+// Hours outside of that are left out, though 24 is taken as midnight.
 #[derive(Default, Debug)]
+pub struct SkipHours {
+    pub hours: Vec<u8>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Days {
     #[default]
     Monday,
@@ -146,10 +357,39 @@ pub enum Days {
     Sunday,
 }
 
-#[derive(Default, Debug)]
-pub struct SkipDays {
-    pub days: [Days; 7],
+impl Days {
+    pub fn parse(name: &str) -> Option<Days> {
+        let day = match name.trim().to_ascii_lowercase().as_str() {
+            "monday" => Days::Monday,
+            "tuesday" => Days::Tuesday,
+            "wednesday" => Days::Wednesday,
+            "thursday" => Days::Thursday,
+            "friday" => Days::Friday,
+            "saturday" => Days::Saturday,
+            "sunday" => Days::Sunday,
+            _ => return None,
+        };
+        Some(day)
+    }
 }
 
+impl From<Weekday> for Days {
+    fn from(day: Weekday) -> Days {
+        match day {
+            Weekday::Mon => Days::Monday,
+            Weekday::Tue => Days::Tuesday,
+            Weekday::Wed => Days::Wednesday,
+            Weekday::Thu => Days::Thursday,
+            Weekday::Fri => Days::Friday,
+            Weekday::Sat => Days::Saturday,
+            Weekday::Sun => Days::Sunday,
+        }
+    }
+}
+
+// The days in GMT when the channel asks not to be fetched, any day that isn't
+// named in full is left out
 #[derive(Default, Debug)]
-pub struct Media {}
\ No newline at end of file
+pub struct SkipDays {
+    pub days: Vec<Days>,
+}
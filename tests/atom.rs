@@ -0,0 +1,52 @@
+use kisserss::atom::Feed;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn prefixed_atom() -> TestResult<()> {
+    let feed = Feed::serialize(
+        r#"<a:feed xmlns:a="http://www.w3.org/2005/Atom">
+            <a:title>Prefixed</a:title>
+            <a:id>urn:feed</a:id>
+            <a:entry>
+                <a:title>First</a:title>
+                <a:id>urn:first</a:id>
+                <a:author><a:name>Ann</a:name></a:author>
+            </a:entry>
+        </a:feed>"#
+            .as_bytes(),
+    )?;
+    assert_eq!(feed.contents.title.data, "Prefixed");
+    assert_eq!(feed.contents.id.data, "urn:feed");
+    assert_eq!(feed.contents.entries.len(), 1);
+    let entry = feed.contents.entries[0].data.as_ref().ok_or("No entry")?;
+    assert_eq!(entry.title.data, "First");
+    assert_eq!(entry.author(), Some("Ann"));
+    Ok(())
+}
+
+#[test]
+fn missing_xmlns() -> TestResult<()> {
+    let feed = Feed::serialize(
+        "<feed><title>Bare</title><entry><title>First</title></entry></feed>".as_bytes(),
+    )?;
+    assert_eq!(feed.contents.title.data, "Bare");
+    assert_eq!(feed.contents.entries.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn other_namespaces_are_skipped() -> TestResult<()> {
+    let feed = Feed::serialize(
+        r#"<feed xmlns="http://www.w3.org/2005/Atom" xmlns:yt="http://www.youtube.com/xml/schemas/2015">
+            <yt:channelId>UC1234</yt:channelId>
+            <title>Channel</title>
+            <entry><yt:title>Not this</yt:title><title>Video</title></entry>
+        </feed>"#
+            .as_bytes(),
+    )?;
+    assert_eq!(feed.contents.title.data, "Channel");
+    let entry = feed.contents.entries[0].data.as_ref().ok_or("No entry")?;
+    assert_eq!(entry.title.data, "Video");
+    Ok(())
+}
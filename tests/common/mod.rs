@@ -0,0 +1,44 @@
+// Feeds made around the part of them a test is about. Each test file only
+// uses some of these.
+#![allow(dead_code)]
+
+use kisserss::{atom, rss};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+// The channel of an RSS 2.0 feed made of `channel`, with every namespace an
+// element in it might need declared
+pub fn channel(channel: &str) -> TestResult<rss::Channel> {
+    let feed = rss::Feed::serialize(
+        format!(
+            r#"<rss version="2.0"
+                xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"
+                xmlns:dc="http://purl.org/dc/elements/1.1/">
+                <channel><title>Feed</title>{channel}</channel>
+            </rss>"#
+        )
+        .as_bytes(),
+    )?;
+    Ok(feed.channel.data)
+}
+
+// The one item of an RSS feed made of `item`
+pub fn item(item: &str) -> TestResult<rss::Item> {
+    let channel = channel(&format!("<item>{item}</item>"))?;
+    let item = channel.items.into_iter().next().ok_or("No item")?;
+    Ok(item.data)
+}
+
+// The one entry of an Atom feed made of `entry`
+pub fn entry(entry: &str) -> TestResult<atom::Entry> {
+    let feed = atom::Feed::serialize(
+        format!(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/elements/1.1/">
+                <title>Feed</title><entry>{entry}</entry>
+            </feed>"#
+        )
+        .as_bytes(),
+    )?;
+    let entry = feed.contents.entries.into_iter().next().ok_or("No entry")?;
+    Ok(entry.data.ok_or("Empty entry")?)
+}
@@ -0,0 +1,69 @@
+use kisserss::date::Date;
+
+// What each date reads as in RFC 3339, or None when it can't be made sense of
+#[rustfmt::skip]
+const DATES: &[(&str, Option<&str>)] = &[
+    // RFC 3339
+    ("2006-01-02T15:04:05+07:00", Some("2006-01-02T15:04:05+07:00")),
+    ("2006-01-02T15:04:05Z", Some("2006-01-02T15:04:05+00:00")),
+    ("2006-01-02T15:04:05.999-07:00", Some("2006-01-02T15:04:05.999-07:00")),
+    ("  2006-01-02T15:04:05Z\n", Some("2006-01-02T15:04:05+00:00")),
+    // Fractional seconds with no offset
+    ("2006-01-02T15:04:05.123", Some("2006-01-02T15:04:05.123+00:00")),
+    // Missing seconds
+    ("2006-01-02T15:04+07:00", Some("2006-01-02T15:04:00+07:00")),
+    ("2006-01-02T15:04+0700", Some("2006-01-02T15:04:00+07:00")),
+    ("2006-01-02T15:04Z", Some("2006-01-02T15:04:00+00:00")),
+    ("2006-01-02T15:04", Some("2006-01-02T15:04:00+00:00")),
+    ("2006-01-02 15:04:05", Some("2006-01-02T15:04:05+00:00")),
+    ("2006-01-02 15:04:05+07:00", Some("2006-01-02T15:04:05+07:00")),
+    // Date only
+    ("2006-01-02", Some("2006-01-02T00:00:00+00:00")),
+    // RFC 822
+    ("Mon, 02 Jan 2006 15:04:05 -0700", Some("2006-01-02T15:04:05-07:00")),
+    ("02 Jan 2006 15:04:05 +0000", Some("2006-01-02T15:04:05+00:00")),
+    ("Mon, 2 January 2006 15:04:05 +07:00", Some("2006-01-02T15:04:05+07:00")),
+    // Two digit years
+    ("Mon, 02 Jan 06 15:04:05 GMT", Some("2006-01-02T15:04:05+00:00")),
+    ("Sat, 02 Jan 99 15:04:05 GMT", Some("1999-01-02T15:04:05+00:00")),
+    // Named zones
+    ("Mon, 02 Jan 2006 15:04:05 GMT", Some("2006-01-02T15:04:05+00:00")),
+    ("Mon, 02 Jan 2006 15:04:05 UT", Some("2006-01-02T15:04:05+00:00")),
+    ("Mon, 02 Jan 2006 15:04:05 EST", Some("2006-01-02T15:04:05-05:00")),
+    ("Mon, 02 Jan 2006 15:04:05 edt", Some("2006-01-02T15:04:05-04:00")),
+    ("Mon, 02 Jan 2006 15:04:05 PST", Some("2006-01-02T15:04:05-08:00")),
+    ("Mon, 02 Jan 2006 15:04:05 A", Some("2006-01-02T15:04:05+00:00")),
+    // Missing seconds and zone
+    ("Mon, 02 Jan 2006 15:04 EST", Some("2006-01-02T15:04:00-05:00")),
+    ("Mon, 02 Jan 2006 15:04:05", Some("2006-01-02T15:04:05+00:00")),
+    // Zones outside of RFC 822 aren't guessed at
+    ("Mon, 02 Jan 2006 15:04:05 CEST", None),
+    ("Mon, 02 Jan 2006 15:04:05 +7", None),
+    // Unparseable
+    ("", None),
+    ("yesterday", None),
+    ("2006-13-02", None),
+    ("Mon, 32 Jan 2006 15:04:05 GMT", None),
+    ("Mon, 02 Foo 2006 15:04:05 GMT", None),
+    ("Mon, 02 Jan 2006 25:04:05 GMT", None),
+    ("Mon, 02 Jan 2006", None),
+];
+
+#[test]
+fn dates() {
+    for (text, expected) in DATES {
+        let date = Date::parse(text);
+        assert_eq!(
+            date.time.map(|time| time.to_rfc3339()).as_deref(),
+            *expected,
+            "{text:?}"
+        );
+    }
+}
+
+#[test]
+fn text_is_kept() {
+    let date = Date::parse(" Mon, 02 Jan 2006 15:04:05 CEST ");
+    assert_eq!(date.text, " Mon, 02 Jan 2006 15:04:05 CEST ");
+    assert_eq!(date.to_string(), date.text);
+}
@@ -0,0 +1,75 @@
+use common::{entry, item};
+
+mod common;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn rss_falls_back_on_dublin_core() -> TestResult<()> {
+    let item = item(
+        "<title>Post</title>\
+         <dc:creator>Ann</dc:creator>\
+         <dc:creator>Bob</dc:creator>\
+         <dc:date>2023-01-02T15:04:05Z</dc:date>",
+    )?;
+    assert_eq!(item.author(), Some("Ann"));
+    let date = item.date().ok_or("No date")?;
+    assert_eq!(date.text, "2023-01-02T15:04:05Z");
+    assert!(date.time.is_some());
+    Ok(())
+}
+
+#[test]
+fn rss_elements_win() -> TestResult<()> {
+    let item = item(
+        "<dc:creator>Ann</dc:creator>\
+         <author>bob@example.org (Bob)</author>\
+         <dc:date>2023-01-02T15:04:05Z</dc:date>\
+         <pubDate>Mon, 02 Jan 2006 15:04:05 GMT</pubDate>",
+    )?;
+    assert_eq!(item.author(), Some("bob@example.org (Bob)"));
+    assert_eq!(
+        item.date().map(|date| date.text.as_str()),
+        Some("Mon, 02 Jan 2006 15:04:05 GMT")
+    );
+    Ok(())
+}
+
+#[test]
+fn rss_without_either() -> TestResult<()> {
+    let item = item("<title>Post</title>")?;
+    assert_eq!(item.author(), None);
+    assert!(item.date().is_none());
+    Ok(())
+}
+
+#[test]
+fn atom_falls_back_on_dublin_core() -> TestResult<()> {
+    let entry = entry(
+        "<title>Post</title>\
+         <dc:creator>Ann</dc:creator>\
+         <dc:date>2023-01-02T15:04:05Z</dc:date>",
+    )?;
+    assert_eq!(entry.author(), Some("Ann"));
+    assert_eq!(
+        entry.date().map(|date| date.text.as_str()),
+        Some("2023-01-02T15:04:05Z")
+    );
+    Ok(())
+}
+
+#[test]
+fn atom_elements_win() -> TestResult<()> {
+    let entry = entry(
+        "<dc:creator>Ann</dc:creator>\
+         <author><name>Bob</name></author>\
+         <dc:date>2023-01-02T15:04:05Z</dc:date>\
+         <updated>2024-01-01T00:00:00Z</updated>",
+    )?;
+    assert_eq!(entry.author(), Some("Bob"));
+    assert_eq!(
+        entry.date().map(|date| date.text.as_str()),
+        Some("2024-01-01T00:00:00Z")
+    );
+    Ok(())
+}
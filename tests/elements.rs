@@ -0,0 +1,125 @@
+use greyxml::{Error, Span, StrReader, Tokens};
+use kisserss::atom::Entry;
+use kisserss::elements::{Element, FromTokens, TokenStream};
+use kisserss::rss::Item;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+// Reads the first element of `xml` the way a feed reads its children
+fn read<T: FromTokens>(xml: &str) -> TestResult<Element<T>> {
+    let tokens = StrReader::new(xml).collect::<Result<Vec<Tokens>, Error>>()?;
+    let mut tokens = TokenStream::new(tokens.into_iter().map(|token| (token, None)));
+    let first = tokens.next().ok_or("No tokens")?;
+    Ok(Element::serialize(first, &mut tokens)?)
+}
+
+#[test]
+fn attributes_are_collected() -> TestResult<()> {
+    let guid: Element<String> = read(r#"<guid isPermaLink="false" a='b'>1234</guid>"#)?;
+    assert_eq!(guid.name, "guid");
+    assert_eq!(
+        guid.attributes,
+        [
+            ("isPermaLink".to_string(), "false".to_string()),
+            ("a".to_string(), "b".to_string())
+        ]
+    );
+    assert_eq!(guid.data, "1234");
+    Ok(())
+}
+
+#[test]
+fn unknown_children_are_skipped_whole() -> TestResult<()> {
+    let item: Element<Item> =
+        read("<item><title>Real</title><foo><title>Nested</title><bar/></foo></item>")?;
+    assert_eq!(
+        item.data.title.map(|title| title.data).as_deref(),
+        Some("Real")
+    );
+    Ok(())
+}
+
+#[test]
+fn atom_source_is_skipped() -> TestResult<()> {
+    let entry: Element<Entry> = read(
+        "<entry>\
+            <title>This feed</title>\
+            <id>urn:this</id>\
+            <source><title>Other feed</title><id>urn:other</id></source>\
+        </entry>",
+    )?;
+    assert_eq!(entry.data.title.data, "This feed");
+    assert_eq!(entry.data.id.data, "urn:this");
+    Ok(())
+}
+
+#[test]
+fn end_tag_is_found_past_children_of_the_same_name() -> TestResult<()> {
+    let item: Element<Item> = read(
+        "<item><item><title>Inner</title></item><title>Outer</title></item><title>After</title>",
+    )?;
+    assert_eq!(
+        item.data.title.map(|title| title.data).as_deref(),
+        Some("Outer")
+    );
+    Ok(())
+}
+
+#[test]
+fn self_closing_children() -> TestResult<()> {
+    let item: Element<Item> = read(r#"<item><empty/><title>Title</title><link/></item>"#)?;
+    assert_eq!(
+        item.data.title.map(|title| title.data).as_deref(),
+        Some("Title")
+    );
+    assert_eq!(item.data.link.map(|link| link.data).as_deref(), Some(""));
+    Ok(())
+}
+
+#[test]
+fn markup_inside_text_is_joined() -> TestResult<()> {
+    let title: Element<String> = read("<title>Tom <b>and</b> Jerry</title>")?;
+    assert_eq!(title.data, "Tom and Jerry");
+    Ok(())
+}
+
+#[test]
+fn unclosed_element() {
+    let at = |row, column| {
+        Some(Span {
+            row,
+            column,
+            length: 3,
+        })
+    };
+    let tokens = vec![
+        (Tokens::OpenNode("item".into()), at(1, 2)),
+        (Tokens::OpenNode("foo".into()), at(2, 4)),
+    ];
+    let mut tokens = TokenStream::new(tokens.into_iter());
+    let first = tokens.next().unwrap();
+    let item = Element::<Item>::serialize(first, &mut tokens);
+    assert!(matches!(
+        item,
+        Err(kisserss::error::FeedError::Unclosed(name, Some(span)))
+            if name == "foo" && (span.row, span.column) == (2, 4)
+    ));
+}
+
+#[test]
+fn parameters_after_the_content_are_ignored() -> TestResult<()> {
+    let tokens = vec![
+        Tokens::OpenNode("title".into()),
+        Tokens::Text("Title".into()),
+        Tokens::ParameterName("a".into()),
+        Tokens::CloseNode("title".into()),
+    ];
+    let mut tokens = TokenStream::new(tokens.into_iter().map(|token| (token, None)));
+    let first = tokens.next().ok_or("No tokens")?;
+    // The end tag isn't mistaken for the parameter's value
+    let title = Element::<String>::serialize(first, &mut tokens)?;
+    assert_eq!(title.data, "Title");
+    assert!(title.attributes.is_empty());
+    assert!(tokens.next().is_none());
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use kisserss::error::FeedError;
+use kisserss::format::{detect, Format};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+#[test]
+fn root_elements() -> TestResult<()> {
+    for (root, format) in [
+        (r#"<rss version="2.0">"#, Format::Rss),
+        (
+            r#"<feed xmlns="http://www.w3.org/2005/Atom">"#,
+            Format::Atom,
+        ),
+        (
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">"#,
+            Format::Rss,
+        ),
+        (
+            "<a:feed xmlns:a=\"http://www.w3.org/2005/Atom\">",
+            Format::Atom,
+        ),
+        ("<rss>", Format::Rss),
+    ] {
+        let document = format!("{DECLARATION}\n{root}");
+        assert_eq!(detect(document.as_bytes(), None)?, format, "{root}");
+    }
+    Ok(())
+}
+
+#[test]
+fn prolog_is_passed_over() -> TestResult<()> {
+    let document = format!(
+        "{DECLARATION}\n\
+         <?xml-stylesheet type=\"text/xsl\" href=\"feed.xsl\"?>\n\
+         <!-- A comment with <feed> in it -->\n\
+         <!DOCTYPE rss [\n\
+             <!ENTITY copy \"&#169;\">\n\
+             <!ELEMENT feed ANY>\n\
+         ]>\n\
+         <rss version=\"0.91\">"
+    );
+    assert_eq!(detect(document.as_bytes(), None)?, Format::Rss);
+    Ok(())
+}
+
+#[test]
+fn byte_order_mark() -> TestResult<()> {
+    let document = format!("\u{FEFF}{DECLARATION}<feed>");
+    assert_eq!(detect(document.as_bytes(), None)?, Format::Atom);
+    assert_eq!(detect("\u{FEFF} {}".as_bytes(), None)?, Format::Json);
+    Ok(())
+}
+
+#[test]
+fn json() -> TestResult<()> {
+    let document = "\n  {\"version\": \"https://jsonfeed.org/version/1.1\"}";
+    assert_eq!(detect(document.as_bytes(), None)?, Format::Json);
+    // Whatever the server says it is
+    assert_eq!(
+        detect(document.as_bytes(), Some("text/html; charset=utf-8"))?,
+        Format::Json
+    );
+    Ok(())
+}
+
+#[test]
+fn document_wins_over_content_type() -> TestResult<()> {
+    assert_eq!(
+        detect(b"<feed>", Some("application/rss+xml"))?,
+        Format::Atom
+    );
+    assert_eq!(detect(b"<rss>", Some("text/html"))?, Format::Rss);
+    Ok(())
+}
+
+#[test]
+fn content_type_is_fallen_back_on() -> TestResult<()> {
+    // The root element is past the end of what was read
+    let start = format!("{DECLARATION}<!-- {} -->", "-".repeat(100));
+    let start = &start.as_bytes()[..60];
+    for (content_type, format) in [
+        ("application/rss+xml", Format::Rss),
+        ("application/rdf+xml", Format::Rss),
+        ("Application/Atom+XML; charset=utf-8", Format::Atom),
+        ("application/feed+json", Format::Json),
+    ] {
+        assert_eq!(detect(start, Some(content_type))?, format, "{content_type}");
+    }
+    assert!(matches!(
+        detect(start, Some("text/xml")),
+        Err(FeedError::UnrecognisedFormat)
+    ));
+    assert!(matches!(
+        detect(start, None),
+        Err(FeedError::UnrecognisedFormat)
+    ));
+    Ok(())
+}
+
+#[test]
+fn other_documents_are_unrecognised() {
+    for (document, content_type) in [
+        ("<!DOCTYPE html><html><body>", Some("application/rss+xml")),
+        ("<html>", Some("application/atom+xml")),
+        ("<html>", None),
+        ("Not found", None),
+    ] {
+        assert!(
+            matches!(
+                detect(document.as_bytes(), content_type),
+                Err(FeedError::UnrecognisedFormat)
+            ),
+            "{document:?}"
+        );
+    }
+}
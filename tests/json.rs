@@ -0,0 +1,187 @@
+use kisserss::error::FeedError;
+use kisserss::json::{Feed, MAX_PAGES};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn version_1_1() -> TestResult<()> {
+    let feed = Feed::serialize(
+        r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "Feed",
+            "next_url": "https://example.org/feed.json?page=2",
+            "authors": [{"name": "Ann"}],
+            "items": [{"id": "1", "content_text": "Hello"}]
+        }"#
+        .as_bytes(),
+    )?;
+    assert_eq!(feed.title, "Feed");
+    assert_eq!(
+        feed.next_url.as_deref(),
+        Some("https://example.org/feed.json?page=2")
+    );
+    assert_eq!(feed.authors[0].name.as_deref(), Some("Ann"));
+    assert_eq!(feed.items[0].content_text.as_deref(), Some("Hello"));
+    Ok(())
+}
+
+#[test]
+fn version_1_author_is_moved_into_authors() -> TestResult<()> {
+    let feed = Feed::serialize(
+        r#"{
+            "version": "https://jsonfeed.org/version/1",
+            "title": "Feed",
+            "author": {"name": "Ann", "url": "https://example.org/ann"},
+            "items": [{"id": "1", "author": {"name": "Bob"}}]
+        }"#
+        .as_bytes(),
+    )?;
+    assert_eq!(feed.authors.len(), 1);
+    assert_eq!(feed.authors[0].name.as_deref(), Some("Ann"));
+    assert_eq!(
+        feed.authors[0].url.as_deref(),
+        Some("https://example.org/ann")
+    );
+    assert_eq!(feed.items[0].author(), Some("Bob"));
+    Ok(())
+}
+
+#[test]
+fn extensions_are_kept() -> TestResult<()> {
+    let feed = Feed::serialize(
+        r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "Feed",
+            "_example": {"about": "https://example.org/"},
+            "unknown": true,
+            "items": [{"id": "1", "_example": {"likes": 3}, "unknown": true}]
+        }"#
+        .as_bytes(),
+    )?;
+    assert_eq!(feed.extensions.keys().collect::<Vec<_>>(), ["_example"]);
+    assert_eq!(feed.extensions["_example"]["about"], "https://example.org/");
+    let item = &feed.items[0];
+    assert_eq!(item.extensions.keys().collect::<Vec<_>>(), ["_example"]);
+    assert_eq!(item.extensions["_example"]["likes"], 3);
+    Ok(())
+}
+
+#[test]
+fn ids() -> TestResult<()> {
+    let feed = Feed::serialize(
+        r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "items": [{"id": "a"}, {"id": 1234}, {"id": 1.5}, {"id": null}, {}]
+        }"#
+        .as_bytes(),
+    )?;
+    let ids: Vec<_> = feed.items.iter().map(|item| item.id.as_str()).collect();
+    assert_eq!(ids, ["a", "1234", "1.5", "", ""]);
+    Ok(())
+}
+
+#[test]
+fn versions() {
+    for (version, supported) in [
+        ("https://jsonfeed.org/version/1", true),
+        ("https://jsonfeed.org/version/1.1", true),
+        ("http://jsonfeed.org/version/1.1/", true),
+        ("https://jsonfeed.org/version/10", false),
+        ("https://jsonfeed.org/version/1.2", false),
+        ("https://example.org/jsonfeed.org/version/1", false),
+        ("", false),
+    ] {
+        let feed = Feed::serialize(format!(r#"{{"version": "{version}"}}"#).as_bytes());
+        if supported {
+            assert!(feed.is_ok(), "{version}");
+        } else {
+            assert!(
+                matches!(feed, Err(FeedError::UnsupportedVersion(ref found)) if found == version),
+                "{version}"
+            );
+        }
+    }
+}
+
+// A page of a feed with one item, `id`, linking on to `next`
+fn page(id: &str, next: Option<&str>) -> TestResult<Feed> {
+    let next_url = next.map_or(String::new(), |next| format!(r#""next_url": "{next}","#));
+    Ok(Feed::serialize(
+        format!(
+            r#"{{
+                "version": "https://jsonfeed.org/version/1.1",
+                {next_url}
+                "items": [{{"id": "{id}"}}]
+            }}"#
+        )
+        .as_bytes(),
+    )?)
+}
+
+fn item_ids(feed: &Feed) -> Vec<&str> {
+    feed.items.iter().map(|item| item.id.as_str()).collect()
+}
+
+#[test]
+fn pages_are_followed() -> TestResult<()> {
+    let mut feed = page("1", Some("https://example.org/2"))?;
+    let mut fetched = vec![];
+    feed.follow_pages("https://example.org/1", |url| {
+        fetched.push(url.to_string());
+        match url {
+            "https://example.org/2" => page("2", Some("https://example.org/3")),
+            _ => page("3", None),
+        }
+    })?;
+    assert_eq!(fetched, ["https://example.org/2", "https://example.org/3"]);
+    assert_eq!(item_ids(&feed), ["1", "2", "3"]);
+    assert_eq!(feed.next_url, None);
+    Ok(())
+}
+
+#[test]
+fn pages_stop_at_one_already_read() -> TestResult<()> {
+    let mut feed = page("1", Some("https://example.org/2"))?;
+    let mut fetched = 0;
+    feed.follow_pages("https://example.org/1", |_| {
+        fetched += 1;
+        page("2", Some("https://example.org/1"))
+    })?;
+    assert_eq!(fetched, 1);
+    assert_eq!(item_ids(&feed), ["1", "2"]);
+    Ok(())
+}
+
+#[test]
+fn pages_stop_at_the_limit() -> TestResult<()> {
+    let mut feed = page("0", Some("https://example.org/1"))?;
+    let mut fetched = 0;
+    feed.follow_pages("https://example.org/0", |_| {
+        fetched += 1;
+        page(
+            &fetched.to_string(),
+            Some(&format!("https://example.org/{}", fetched + 1)),
+        )
+    })?;
+    assert_eq!(fetched, MAX_PAGES);
+    assert_eq!(feed.items.len(), MAX_PAGES + 1);
+    // Where it would have gone on to
+    assert_eq!(
+        feed.next_url,
+        Some(format!("https://example.org/{}", MAX_PAGES + 1))
+    );
+    Ok(())
+}
+
+#[test]
+fn pages_read_before_a_failure_are_kept() -> TestResult<()> {
+    let mut feed = page("1", Some("https://example.org/2"))?;
+    let result = feed.follow_pages("https://example.org/1", |url| match url {
+        "https://example.org/2" => page("2", Some("https://example.org/3")),
+        _ => Err("Not found".into()),
+    });
+    assert!(result.is_err());
+    assert_eq!(item_ids(&feed), ["1", "2"]);
+    assert_eq!(feed.next_url.as_deref(), Some("https://example.org/3"));
+    Ok(())
+}
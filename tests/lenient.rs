@@ -0,0 +1,91 @@
+use kisserss::atom;
+use kisserss::error::FeedError;
+use kisserss::rss;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+const BAD_TTL: &str = r#"<rss version="2.0">
+<channel>
+    <title>Feed</title>
+    <ttl>abc</ttl>
+    <item><title>First</title></item>
+</channel>
+</rss>"#;
+
+// The row and column a warning points at
+fn position(warning: &FeedError) -> Option<(usize, usize)> {
+    warning.span().map(|span| (span.row, span.column))
+}
+
+#[test]
+fn bad_number_is_a_warning() -> TestResult<()> {
+    let (feed, warnings) = rss::Feed::serialize_lenient(BAD_TTL.as_bytes())?;
+    assert_eq!(feed.channel.data.title.data, "Feed");
+    assert!(feed.channel.data.ttl.is_none());
+    assert_eq!(feed.channel.data.items.len(), 1);
+    assert!(matches!(
+        warnings.as_slice(),
+        [FeedError::InvalidNumber(name, text, _)] if name == "ttl" && text == "abc"
+    ));
+    // The name in the start tag of <ttl>
+    assert_eq!(position(&warnings[0]), Some((4, 6)));
+    assert_eq!(
+        warnings[0].to_string(),
+        "4:6: ttl should be a number but was \"abc\""
+    );
+    Ok(())
+}
+
+#[test]
+fn bad_number_fails_strictly() {
+    let feed = rss::Feed::serialize(BAD_TTL.as_bytes());
+    assert!(matches!(feed, Err(FeedError::InvalidNumber(..))));
+    assert_eq!(feed.err().as_ref().and_then(position), Some((4, 6)));
+}
+
+#[test]
+fn unrecoverable_errors_point_at_their_element() {
+    for (xml, expected) in [
+        // What was found in the channel's place
+        ("<rss version=\"2.0\">\n  <item/>\n</rss>", (2, 4)),
+        // The root that has no channel
+        ("<rss version=\"2.0\"></rss>", (1, 2)),
+        (
+            "\n<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"><item/></rdf:RDF>",
+            (2, 2),
+        ),
+    ] {
+        let feed = rss::Feed::serialize_lenient(xml.as_bytes());
+        assert_eq!(feed.err().as_ref().and_then(position), Some(expected), "{xml}");
+    }
+}
+
+#[test]
+fn reader_warnings_are_kept() -> TestResult<()> {
+    let (feed, warnings) =
+        atom::Feed::serialize_lenient("<feed><title>Unclosed</feed>".as_bytes())?;
+    assert_eq!(feed.contents.title.data, "Unclosed");
+    assert!(matches!(warnings.as_slice(), [FeedError::Xml(_)]));
+    Ok(())
+}
+
+#[test]
+fn the_rest_of_the_element_is_kept() -> TestResult<()> {
+    let (feed, warnings) = rss::Feed::serialize_lenient(
+        r#"<rss><channel>
+  <image>
+    <url>logo.png</url>
+    <width>wide</width>
+    <height>32</height>
+  </image>
+</channel></rss>"#
+            .as_bytes(),
+    )?;
+    let image = feed.channel.data.image.ok_or("No image")?.data;
+    assert_eq!(image.url.data, "logo.png");
+    assert!(image.width.is_none());
+    assert_eq!(image.height.map(|height| height.data), Some(32));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(position(&warnings[0]), Some((4, 6)));
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use kisserss::{atom, rss};
+use std::fs::File;
+use std::io::BufReader;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn rss_group() -> TestResult<()> {
+    let feed = rss::Feed::serialize(BufReader::new(File::open("tests/media.rss")?))?;
+    let media = feed.channel.data.items[0]
+        .data
+        .media
+        .as_ref()
+        .ok_or("No media")?;
+    assert!(media.contents.is_none());
+    let groups = media.groups.as_ref().ok_or("No groups")?;
+    assert_eq!(groups.len(), 1);
+    let group = &groups[0].data;
+    assert_eq!(
+        group
+            .details
+            .title
+            .as_ref()
+            .map(|title| title.data.as_str()),
+        Some("The video")
+    );
+    let credit = &group.details.credits.as_ref().ok_or("No credits")?[0];
+    assert_eq!(credit.data, "Ann");
+    assert_eq!(credit.attributes, [("role".into(), "author".into())]);
+
+    let contents: Vec<_> = media.contents().collect();
+    assert_eq!(contents.len(), 2);
+    let first = contents[0];
+    assert_eq!(
+        first.url.as_deref(),
+        Some("https://example.org/video-1080.mp4")
+    );
+    assert_eq!(first.mime_type.as_deref(), Some("video/mp4"));
+    assert_eq!(first.medium.as_deref(), Some("video"));
+    assert_eq!(first.duration, Some(95));
+    assert_eq!((first.width, first.height), (Some(1920), Some(1080)));
+
+    // The group's thumbnail and the second content's, broadest first
+    let thumbnails: Vec<_> = media
+        .details()
+        .flat_map(|details| details.thumbnails.iter().flatten())
+        .map(|thumbnail| &thumbnail.data)
+        .collect();
+    assert_eq!(thumbnails.len(), 2);
+    assert_eq!(thumbnails[0].url, "https://example.org/video.jpg");
+    assert_eq!(thumbnails[0].time.as_deref(), Some("00:00:05.000"));
+    assert_eq!(thumbnails[1].url, "https://example.org/video-480.jpg");
+    assert_eq!(
+        (thumbnails[1].width, thumbnails[1].height),
+        (Some(854), Some(480))
+    );
+    Ok(())
+}
+
+#[test]
+fn rss_content() -> TestResult<()> {
+    let feed = rss::Feed::serialize(BufReader::new(File::open("tests/media.rss")?))?;
+    let media = feed.channel.data.items[1]
+        .data
+        .media
+        .as_ref()
+        .ok_or("No media")?;
+    assert!(media.groups.is_none());
+    let content = &media.contents.as_ref().ok_or("No contents")?[0].data;
+    assert_eq!(
+        content.url.as_deref(),
+        Some("https://example.org/photo.jpg")
+    );
+    assert_eq!(content.medium.as_deref(), Some("image"));
+    // Left out rather than failing the feed
+    assert_eq!(content.width, None);
+    assert_eq!(
+        content
+            .details
+            .description
+            .as_ref()
+            .map(|description| description.data.as_str()),
+        Some("The photo")
+    );
+
+    let thumbnail = &media.details.thumbnails.as_ref().ok_or("No thumbnails")?[0];
+    assert_eq!(thumbnail.data.url, "https://example.org/photo-small.jpg");
+    assert_eq!(thumbnail.data.width, None);
+    let player = media.details.player.as_ref().ok_or("No player")?;
+    assert_eq!(player.data.url, "https://example.org/photo");
+    Ok(())
+}
+
+#[test]
+fn youtube_entry() -> TestResult<()> {
+    let feed = atom::Feed::serialize(BufReader::new(File::open("tests/youtube.atom")?))?;
+    assert_eq!(feed.contents.title.data, "A Channel");
+    let entry = feed.contents.entries[0].data.as_ref().ok_or("No entry")?;
+    assert_eq!(entry.title.data, "The first video");
+    assert_eq!(entry.author(), Some("A Channel"));
+
+    let media = entry.media.as_ref().ok_or("No media")?;
+    let group = &media.groups.as_ref().ok_or("No groups")?[0].data;
+    assert_eq!(
+        group
+            .details
+            .description
+            .as_ref()
+            .map(|description| description.data.as_str()),
+        Some("What the video is about.\nOver two lines.")
+    );
+    let content = media.contents().next().ok_or("No content")?;
+    assert_eq!(
+        content.url.as_deref(),
+        Some("https://www.youtube.com/v/AAAAAAAAAAA?version=3")
+    );
+    assert_eq!((content.width, content.height), (Some(640), Some(390)));
+    let thumbnail = &group.details.thumbnails.as_ref().ok_or("No thumbnails")?[0];
+    assert_eq!(
+        thumbnail.data.url,
+        "https://i1.ytimg.com/vi/AAAAAAAAAAA/hqdefault.jpg"
+    );
+    assert_eq!(
+        (thumbnail.data.width, thumbnail.data.height),
+        (Some(480), Some(360))
+    );
+    Ok(())
+}
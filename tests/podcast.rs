@@ -0,0 +1,57 @@
+use kisserss::podcast::{Duration, Episode};
+
+mod common;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+// What the itunes elements in `item` say about the episode
+fn episode(item: &str) -> TestResult<Episode> {
+    let item = common::item(&format!("<title>Episode</title>{item}"))?;
+    Ok(item.podcast.ok_or("No podcast metadata")?)
+}
+
+#[test]
+fn durations() {
+    for (text, seconds) in [
+        ("3600", Some(3600)),
+        ("45:30", Some(2730)),
+        ("1:02:03", Some(3723)),
+        (" 90.5 ", Some(90)),
+        ("45 min", None),
+        ("1:2:3:4", None),
+        ("", None),
+    ] {
+        assert_eq!(Duration::parse(text).map(|d| d.0), seconds, "{text:?}");
+    }
+}
+
+#[test]
+fn numbers_are_read() -> TestResult<()> {
+    let episode = episode(
+        "<itunes:duration>1:02:03</itunes:duration>\
+         <itunes:episode>12</itunes:episode>\
+         <itunes:season>2</itunes:season>",
+    )?;
+    assert_eq!(episode.duration.map(|duration| duration.0), Some(3723));
+    assert_eq!(episode.episode, Some(12));
+    assert_eq!(episode.season, Some(2));
+    Ok(())
+}
+
+#[test]
+fn bad_numbers_are_left_out() -> TestResult<()> {
+    let episode = episode(
+        "<itunes:duration>45 min</itunes:duration>\
+         <itunes:episode>bonus</itunes:episode>\
+         <itunes:season>two</itunes:season>\
+         <itunes:author>Host</itunes:author>",
+    )?;
+    assert!(episode.duration.is_none());
+    assert!(episode.episode.is_none());
+    assert!(episode.season.is_none());
+    assert_eq!(
+        episode.author.map(|author| author.data).as_deref(),
+        Some("Host")
+    );
+    Ok(())
+}
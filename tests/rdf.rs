@@ -0,0 +1,122 @@
+use kisserss::rss::{Feed, Version};
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+// The titles of a feed's items, in the order they're listed
+fn titles(feed: &Feed) -> Vec<&str> {
+    feed.channel
+        .data
+        .items
+        .iter()
+        .filter_map(|item| item.data.title.as_ref().map(|title| title.data.as_str()))
+        .collect()
+}
+
+#[test]
+fn rss_1_0() -> TestResult<()> {
+    let feed = Feed::serialize(
+        r#"<?xml version="1.0"?>
+        <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/">
+            <channel rdf:about="https://example.org/">
+                <title>Feed</title>
+                <image rdf:resource="https://example.org/logo.png"/>
+                <items>
+                    <rdf:Seq>
+                        <rdf:li rdf:resource="https://example.org/2"/>
+                        <rdf:li resource="https://example.org/1"/>
+                    </rdf:Seq>
+                </items>
+            </channel>
+            <image rdf:about="https://example.org/logo.png">
+                <url>https://example.org/logo.png</url>
+            </image>
+            <item rdf:about="https://example.org/1"><title>First</title></item>
+            <item rdf:about="https://example.org/2"><title>Second</title></item>
+        </rdf:RDF>"#
+            .as_bytes(),
+    )?;
+    assert_eq!(feed.version, Version::Rss10);
+    assert_eq!(feed.channel.data.title.data, "Feed");
+    let image = feed.channel.data.image.as_ref().ok_or("No image")?;
+    assert_eq!(image.data.url.data, "https://example.org/logo.png");
+    // In the order the Seq gives them
+    assert_eq!(titles(&feed), ["Second", "First"]);
+    Ok(())
+}
+
+#[test]
+fn items_missing_from_the_sequence_come_last() -> TestResult<()> {
+    let feed = Feed::serialize(
+        r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/">
+            <channel>
+                <title>Feed</title>
+                <items><rdf:Seq><rdf:li rdf:resource="c"/><rdf:li rdf:resource="a"/></rdf:Seq></items>
+            </channel>
+            <item rdf:about="a"><title>A</title></item>
+            <item rdf:about="x"><title>X</title></item>
+            <item><title>No about</title></item>
+            <item rdf:about="c"><title>C</title></item>
+            <item rdf:about="y"><title>Y</title></item>
+        </rdf:RDF>"#
+            .as_bytes(),
+    )?;
+    assert_eq!(titles(&feed), ["C", "A", "X", "No about", "Y"]);
+    Ok(())
+}
+
+#[test]
+fn rss_0_90() -> TestResult<()> {
+    let feed = Feed::serialize(
+        r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://my.netscape.com/rdf/simple/0.9/">
+            <channel><title>Old feed</title></channel>
+            <item><title>First</title></item>
+            <item><title>Second</title></item>
+        </rdf:RDF>"#
+            .as_bytes(),
+    )?;
+    assert_eq!(feed.version, Version::Rss090);
+    assert_eq!(feed.channel.data.title.data, "Old feed");
+    // With no Seq they stay in document order
+    assert_eq!(titles(&feed), ["First", "Second"]);
+    Ok(())
+}
+
+#[test]
+fn rdf_without_a_channel() {
+    let feed = Feed::serialize(
+        r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"><item/></rdf:RDF>"#
+            .as_bytes(),
+    );
+    assert!(feed.is_err());
+}
+
+#[test]
+fn versions() {
+    for (text, version) in [
+        ("0.9", Version::Rss090),
+        ("0.90", Version::Rss090),
+        ("0.91", Version::Rss091),
+        ("0.92", Version::Rss092),
+        ("0.93", Version::Rss093),
+        ("0.94", Version::Rss094),
+        ("1.0", Version::Rss10),
+        ("2.0", Version::Rss20),
+        (" 2.0 ", Version::Rss20),
+        ("2.0.1", Version::Rss20),
+        ("3.0", Version::Unknown("3.0".into())),
+        ("", Version::Unknown(String::new())),
+    ] {
+        assert_eq!(Version::parse(text), version, "{text:?}");
+    }
+}
+
+#[test]
+fn version_attribute() -> TestResult<()> {
+    let feed = Feed::serialize(r#"<rss version="0.91"><channel/></rss>"#.as_bytes())?;
+    assert_eq!(feed.version, Version::Rss091);
+    let feed = Feed::serialize(r#"<rss version="two"><channel/></rss>"#.as_bytes())?;
+    assert_eq!(feed.version, Version::Unknown("two".into()));
+    let feed = Feed::serialize("<rss><channel/></rss>".as_bytes())?;
+    assert_eq!(feed.version, Version::Unknown(String::new()));
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use chrono::{Duration, TimeZone, Utc};
+use common::channel;
+use kisserss::rss::{Days, DEFAULT_TTL};
+
+mod common;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn day_names() {
+    assert_eq!(Days::parse("Monday"), Some(Days::Monday));
+    assert_eq!(Days::parse(" sunday\n"), Some(Days::Sunday));
+    assert_eq!(Days::parse("SATURDAY"), Some(Days::Saturday));
+    assert_eq!(Days::parse("Mon"), None);
+    assert_eq!(Days::parse(""), None);
+}
+
+#[test]
+fn skip_hours() -> TestResult<()> {
+    let channel = channel(
+        "<skipHours><hour>24</hour><hour>3</hour><hour>25</hour><hour>noon</hour></skipHours>",
+    )?;
+    let hours = channel.skip_hours.ok_or("No skipHours")?.data.hours;
+    // 24 is midnight, the rest aren't hours at all
+    assert_eq!(hours, [0, 3]);
+    Ok(())
+}
+
+#[test]
+fn skip_days() -> TestResult<()> {
+    let channel = channel("<skipDays><day>Saturday</day><day>Someday</day></skipDays>")?;
+    let days = channel.skip_days.ok_or("No skipDays")?.data.days;
+    assert_eq!(days, [Days::Saturday]);
+    Ok(())
+}
+
+#[test]
+fn skips() -> TestResult<()> {
+    let channel = channel(
+        "<skipHours><hour>24</hour><hour>13</hour></skipHours>\
+         <skipDays><day>Sunday</day></skipDays>",
+    )?;
+    // 2024-01-01 was a Monday
+    assert!(channel.skips(Utc.with_ymd_and_hms(2024, 1, 1, 0, 30, 0).unwrap()));
+    assert!(channel.skips(Utc.with_ymd_and_hms(2024, 1, 1, 13, 0, 0).unwrap()));
+    assert!(!channel.skips(Utc.with_ymd_and_hms(2024, 1, 1, 12, 59, 0).unwrap()));
+    assert!(channel.skips(Utc.with_ymd_and_hms(2024, 1, 7, 12, 0, 0).unwrap()));
+    Ok(())
+}
+
+#[test]
+fn due_after_the_ttl() -> TestResult<()> {
+    let fetched = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+    let half_hourly = channel("<ttl>30</ttl>")?;
+    assert!(!half_hourly.due(fetched, fetched + Duration::minutes(29)));
+    assert!(half_hourly.due(fetched, fetched + Duration::minutes(30)));
+    // Without a ttl, or with one of 0, it waits the default
+    let default = Duration::minutes(DEFAULT_TTL.into());
+    for unsaid in [channel("")?, channel("<ttl>0</ttl>")?] {
+        assert!(!unsaid.due(fetched, fetched + default - Duration::minutes(1)));
+        assert!(unsaid.due(fetched, fetched + default));
+    }
+    Ok(())
+}
+
+#[test]
+fn scheduled_refreshes_skip_what_the_feed_asks() -> TestResult<()> {
+    let channel = channel(
+        "<ttl>30</ttl>\
+         <skipHours><hour>13</hour></skipHours>\
+         <skipDays><day>Sunday</day></skipDays>",
+    )?;
+    // 2024-01-01 was a Monday
+    let fetched = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+    assert!(channel.due(fetched, fetched + Duration::minutes(59)));
+    assert!(!channel.due(fetched, fetched + Duration::minutes(60)));
+    assert!(channel.due(fetched, fetched + Duration::minutes(120)));
+    assert!(!channel.due(fetched, fetched + Duration::days(6)));
+    // However long it has been
+    let long_ago = fetched - Duration::days(365);
+    assert!(!channel.due(long_ago, fetched + Duration::minutes(60)));
+    Ok(())
+}
+
+#[test]
+fn nothing_is_skipped_without_asking() -> TestResult<()> {
+    let channel = channel("")?;
+    assert!(!channel.skips(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()));
+    Ok(())
+}
+
+#[test]
+fn cloud_attributes() -> TestResult<()> {
+    let channel = channel(
+        r#"<cloud domain="rpc.sys.com" port="80" path="/RPC2"
+            registerProcedure="myCloud.rssPleaseNotify" protocol="xml-rpc"/>"#,
+    )?;
+    let cloud = channel.cloud.ok_or("No cloud")?.data;
+    assert_eq!(cloud.domain, "rpc.sys.com");
+    assert_eq!(cloud.port, Some(80));
+    assert_eq!(cloud.path, "/RPC2");
+    assert_eq!(cloud.register_procedure, "myCloud.rssPleaseNotify");
+    assert_eq!(cloud.protocol, "xml-rpc");
+    Ok(())
+}
+
+#[test]
+fn cloud_port_is_lenient() -> TestResult<()> {
+    let channel = channel(r#"<cloud domain="rpc.sys.com" port="http"/>"#)?;
+    let cloud = channel.cloud.ok_or("No cloud")?.data;
+    assert_eq!(cloud.domain, "rpc.sys.com");
+    assert_eq!(cloud.port, None);
+    Ok(())
+}
+
+#[test]
+fn enclosures() -> TestResult<()> {
+    let channel = channel(
+        r#"<item>
+            <title>Episode</title>
+            <enclosure url="https://example.org/e.mp3" length="12345678" type="audio/mpeg"/>
+            <enclosure url="https://example.org/e.ogg" type="audio/ogg"/>
+            <enclosure url="https://example.org/e.m4a" length="0" type="audio/mp4"/>
+            <enclosure url="https://example.org/e.opus" length="big" type="audio/opus"/>
+        </item>"#,
+    )?;
+    let item = &channel.items[0].data;
+    let enclosures: Vec<_> = item
+        .enclosures
+        .iter()
+        .flatten()
+        .map(|enclosure| {
+            let enclosure = &enclosure.data;
+            (
+                enclosure.url.as_str(),
+                enclosure.length,
+                enclosure.mime_type.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        enclosures,
+        [
+            ("https://example.org/e.mp3", Some(12345678), "audio/mpeg"),
+            // Missing, 0 and not a number all mean the length isn't known
+            ("https://example.org/e.ogg", None, "audio/ogg"),
+            ("https://example.org/e.m4a", None, "audio/mp4"),
+            ("https://example.org/e.opus", None, "audio/opus"),
+        ]
+    );
+    Ok(())
+}